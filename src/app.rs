@@ -1,148 +1,356 @@
+use chrono::NaiveDateTime;
 use eframe::egui;
 use rust_i18n::t;
-use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
-use std::thread;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::Instant;
 
-use crate::backup::{backup_world_to_path_with_progress, get_hytale_worlds_path, import_world};
+use crate::backup::{backup_world_to_path_with_progress, import_world, inspect_archive};
+use crate::batch::{export_batch, unique_file_name, BatchJob};
+use crate::consistency::{detect_usage_of_worlds, detect_world_usage, WorldUsage};
+use crate::history::{load_history, HistoryEntry};
+use crate::jobs::{JobQueue, JobReport};
 use crate::log_filter::{detect_log_level, should_filter_line, LogLevel};
-use crate::models::{BackupProgress, WorldInfo};
+use crate::models::{
+    ArchiveInfo, BackupInfo, ExportOptions, JobKind, LogInfo, PendingImport, PendingRestore,
+    PendingVerify, WorldInfo, WorldView,
+};
+use crate::recycle::{purge_local_trash, TrashWorker, TrashedFile};
+use crate::retention::prune_after_export;
+use crate::scanner::{ArchiveScanner, WorldScanner};
+use crate::scheduler::{ScheduleLock, Scheduler};
+use crate::session::SessionMonitor;
+use crate::settings::{
+    external_save_roots, load_settings, merge_save_roots, save_settings, set_aside_settings,
+    SaveRoot, Settings,
+};
+use crate::signing::{load_identity, SignatureStatus, SigningIdentity};
+use crate::template::{render_unique, TemplateContext};
 use crate::utils::{
-    format_size, get_directory_size, get_last_played, get_latest_log, get_world_backups,
-    open_file_in_finder,
+    format_size, get_directory_size, get_latest_log, get_world_backups, open_file_in_finder,
 };
+use crate::volumes::{is_first_volume, is_volume};
+use crate::watcher::WorldWatcher;
+use crate::worlds::load_worlds;
+
+mod backups;
+mod jobs;
+mod keys;
+mod schedule;
+mod settings;
+mod world_list;
+
+pub use keys::KeyManager;
+pub use settings::SettingsWindow;
 
 /// Maximum height for tab content areas
 pub const TAB_CONTENT_MAX_HEIGHT: f32 = 260.0;
 
+/// Age and count the in-game backup selection rules start with
+const DEFAULT_BACKUP_CLEANUP_DAYS: u64 = 30;
+const DEFAULT_BACKUP_KEEP_NEWEST: usize = 10;
+
 pub struct HytaleBackupApp {
     pub status_message: String,
+    pub settings: Settings,
+    /// Save roots from the environment and command line, not persisted
+    pub external_roots: Vec<SaveRoot>,
+    pub settings_window: Option<SettingsWindow>,
     pub worlds: Vec<WorldInfo>,
+    pub scanner: WorldScanner,
+    pub archive_scanner: ArchiveScanner,
+    pub watcher: WorldWatcher,
     pub selected_world: Option<usize>,
-    pub selected_tab: usize,
-    pub include_logs: bool,
-    pub include_backups: bool,
-    pub progress: Arc<Mutex<BackupProgress>>,
-    pub pending_delete_backup: Option<PathBuf>,
-    pub pending_import: Option<(PathBuf, String)>,
-    pub hide_info_logs: bool,
+    /// Further worlds selected with Ctrl-click for a batch export
+    pub selected_worlds: HashSet<PathBuf>,
+    /// Text the world list is filtered by
+    pub world_search: String,
+    /// Cached backups and log of the selected world
+    pub world_view: Option<WorldView>,
+    pub encrypt_export: bool,
+    pub sign_export: bool,
+    pub signing_identity: Option<SigningIdentity>,
+    pub key_manager: Option<KeyManager>,
+    /// Password and confirmation entered in the export password dialog
+    pub pending_export_password: Option<(String, String)>,
+    /// Signs that the selected world is in use, shown before an export starts
+    pub pending_usage_warning: Option<WorldUsage>,
+    /// Worlds of a batch export waiting for the usage warning or password dialog
+    pub pending_batch: Option<Vec<usize>>,
+    pub jobs: JobQueue,
+    /// Finished jobs, oldest first
+    pub history: Vec<HistoryEntry>,
+    /// Encrypted archive waiting for its password before it is verified
+    pub pending_verify: Option<PendingVerify>,
+    pub scheduler: Scheduler,
+    /// Held while this window runs the automatic backups instead of the daemon
+    pub schedule_lock: Option<ScheduleLock>,
+    /// Last attempt to take the schedule lock
+    pub schedule_lock_checked: Option<Instant>,
+    /// Watches for ended play sessions while backups on game exit are enabled
+    pub session_monitor: Option<SessionMonitor>,
+    /// In-game backups of the selected world chosen for a bulk deletion
+    pub selected_backups: HashSet<PathBuf>,
+    /// Age in days and count used by the rules that select backups
+    pub backup_cleanup_days: u64,
+    pub backup_keep_newest: usize,
+    /// In-game backups waiting for confirmation in the delete dialog
+    pub pending_delete_backups: Option<Vec<BackupInfo>>,
+    pub pending_restore: Option<PendingRestore>,
+    /// Status message of the last deletion and the files it moved to the trash
+    pub trash_undo: Option<(String, Vec<TrashedFile>)>,
+    pub trash: TrashWorker,
+    pub pending_import: Option<PendingImport>,
 }
 
 impl HytaleBackupApp {
-    pub fn new() -> Self {
-        let worlds = Self::load_worlds();
-        Self {
-            status_message: String::new(),
-            worlds,
+    pub fn new(ctx: &egui::Context, cli_saves_dirs: Vec<PathBuf>) -> Self {
+        // An unreadable settings file is kept aside rather than overwritten
+        let (settings, status_message) = match load_settings() {
+            Ok(settings) => (settings, String::new()),
+            Err(e) => {
+                let mut message = format!("{} {}", t!("app.error"), e);
+                if let Ok(moved) = set_aside_settings() {
+                    message.push_str(&format!(
+                        "\n{}",
+                        t!("app.settings_set_aside", path = moved.to_string_lossy())
+                    ));
+                }
+                (Settings::default(), message)
+            }
+        };
+        let external_roots = external_save_roots(&cli_saves_dirs);
+        let mut app = Self {
+            status_message,
+            settings,
+            external_roots,
+            settings_window: None,
+            worlds: Vec::new(),
+            scanner: WorldScanner::default(),
+            archive_scanner: ArchiveScanner::default(),
+            watcher: WorldWatcher::default(),
             selected_world: None,
-            selected_tab: 0,
-            include_logs: true,
-            include_backups: true,
-            progress: Arc::new(Mutex::new(BackupProgress::default())),
-            pending_delete_backup: None,
+            selected_worlds: HashSet::new(),
+            world_search: String::new(),
+            world_view: None,
+            encrypt_export: false,
+            sign_export: false,
+            signing_identity: load_identity(),
+            key_manager: None,
+            pending_export_password: None,
+            pending_usage_warning: None,
+            pending_batch: None,
+            jobs: JobQueue::default(),
+            history: load_history(),
+            pending_verify: None,
+            scheduler: Scheduler::load(),
+            schedule_lock: None,
+            schedule_lock_checked: None,
+            session_monitor: None,
+            selected_backups: HashSet::new(),
+            backup_cleanup_days: DEFAULT_BACKUP_CLEANUP_DAYS,
+            backup_keep_newest: DEFAULT_BACKUP_KEEP_NEWEST,
+            pending_delete_backups: None,
+            pending_restore: None,
+            trash_undo: None,
+            trash: TrashWorker::default(),
             pending_import: None,
-            hide_info_logs: false,
+        };
+        app.refresh_worlds(ctx);
+        purge_local_trash();
+        app
+    }
+
+    /// All save roots, configured ones first
+    pub fn save_roots(&self) -> Vec<SaveRoot> {
+        merge_save_roots(&self.settings.save_roots, &self.external_roots)
+    }
+
+    /// Places a world can be imported into: save roots, then server installs
+    pub fn import_targets(&self) -> Vec<SaveRoot> {
+        let mut targets = self.save_roots();
+        targets.extend(self.settings.server_profiles.iter().map(|server| SaveRoot {
+            name: server.name.clone(),
+            path: server.path.clone(),
+        }));
+        targets
+    }
+
+    fn is_server_dir(&self, path: &Path) -> bool {
+        self.settings
+            .server_profiles
+            .iter()
+            .any(|server| server.path == path)
+    }
+
+    /// Lists the worlds and starts calculating their details in the background
+    ///
+    /// The selected world stays selected if it still exists.
+    pub fn refresh_worlds(&mut self, ctx: &egui::Context) {
+        let selected = self
+            .selected_world
+            .and_then(|index| self.worlds.get(index))
+            .map(|world| world.path.clone());
+
+        let roots = self.save_roots();
+        self.world_view = None;
+        self.worlds = load_worlds(&roots, &self.settings.server_profiles);
+        self.selected_world =
+            selected.and_then(|path| self.worlds.iter().position(|world| world.path == path));
+        let worlds = &self.worlds;
+        self.selected_worlds
+            .retain(|path| worlds.iter().any(|world| world.path == *path));
+
+        let watched: Vec<PathBuf> = roots
+            .into_iter()
+            .map(|root| root.path)
+            .chain(
+                self.settings
+                    .server_profiles
+                    .iter()
+                    .map(|server| server.path.clone()),
+            )
+            .collect();
+        if let Some(monitor) = &self.session_monitor {
+            monitor.set_worlds(self.worlds.iter().map(|world| world.path.clone()).collect());
+        }
+        if self.watcher.roots() != watched {
+            self.watcher = WorldWatcher::new(watched, ctx);
         }
+
+        self.scan_missing_details(ctx);
     }
 
-    pub fn load_worlds() -> Vec<WorldInfo> {
-        match get_hytale_worlds_path() {
-            Ok(base_path) => {
-                if base_path.exists() {
-                    fs::read_dir(&base_path)
-                        .map(|entries| {
-                            entries
-                                .filter_map(|entry| entry.ok())
-                                .filter(|entry| entry.path().is_dir())
-                                .filter_map(|entry| {
-                                    let name = entry.file_name().to_str()?.to_string();
-                                    let path = entry.path();
-                                    let size = get_directory_size(&path);
-                                    let last_played = get_last_played(&path);
-                                    Some(WorldInfo {
-                                        name,
-                                        path,
-                                        size,
-                                        last_played,
-                                    })
-                                })
-                                .collect()
-                        })
-                        .unwrap_or_default()
-                } else {
-                    Vec::new()
+    /// Starts calculating the details of worlds that have none
+    fn scan_missing_details(&self, ctx: &egui::Context) {
+        let paths: Vec<PathBuf> = self
+            .worlds
+            .iter()
+            .filter(|world| world.details.is_none())
+            .map(|world| world.path.clone())
+            .collect();
+        if !paths.is_empty() {
+            self.scanner.scan(paths, ctx);
+        }
+    }
+
+    /// Applies changes the file watcher saw in the save roots
+    fn apply_file_changes(&mut self, ctx: &egui::Context) {
+        let Some(changes) = self.watcher.take_changes() else {
+            return;
+        };
+
+        for path in &changes.worlds {
+            self.scanner.invalidate(path);
+        }
+        if self
+            .world_view
+            .as_ref()
+            .is_some_and(|view| changes.worlds.contains(&view.path))
+        {
+            self.world_view = None;
+        }
+        if changes.world_list {
+            self.refresh_worlds(ctx);
+        } else {
+            for world in &mut self.worlds {
+                if changes.worlds.contains(&world.path) {
+                    world.details = None;
                 }
             }
-            Err(_) => Vec::new(),
+            self.scan_missing_details(ctx);
         }
     }
 
-    pub fn refresh_worlds(&mut self) {
-        self.worlds = Self::load_worlds();
-        self.selected_world = None;
+    /// Copies details finished by the background scan into the world list
+    fn apply_scan_results(&mut self) {
+        for world in self
+            .worlds
+            .iter_mut()
+            .filter(|world| world.details.is_none())
+        {
+            world.details = self.scanner.details(&world.path);
+        }
     }
 
-    fn render_delete_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(backup_path) = self.pending_delete_backup.clone() {
-            egui::Window::new(t!("app.confirm_delete_title"))
+    fn render_import_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(mut pending) = self.pending_import.clone() {
+            egui::Window::new(t!("app.confirm_import_title"))
                 .collapsible(false)
                 .resizable(false)
                 .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
                 .show(ctx, |ui| {
-                    ui.label(t!("app.confirm_delete_message"));
+                    ui.label(t!("app.confirm_import_message"));
+                    ui.add_space(10.0);
+                    ui.label(egui::RichText::new(&pending.world_name).strong().size(16.0));
                     ui.add_space(10.0);
                     ui.label(
-                        egui::RichText::new(
-                            backup_path
-                                .file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .to_string(),
-                        )
-                        .strong(),
+                        egui::RichText::new(t!("app.confirm_import_warning"))
+                            .color(egui::Color32::from_rgb(255, 180, 100)),
                     );
-                    ui.add_space(15.0);
 
-                    ui.horizontal(|ui| {
-                        if ui.button(t!("app.cancel")).clicked() {
-                            self.pending_delete_backup = None;
+                    ui.add_space(10.0);
+                    self.render_signature_status(ui, &pending.info.signature);
+
+                    let roots = self.import_targets();
+                    if roots.len() > 1 {
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            ui.label(t!("app.import_target"));
+                            let selected = roots
+                                .iter()
+                                .find(|root| root.path == pending.target_root)
+                                .map(|root| root.name.clone())
+                                .unwrap_or_default();
+                            egui::ComboBox::from_id_salt("import_target")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    for root in &roots {
+                                        ui.selectable_value(
+                                            &mut pending.target_root,
+                                            root.path.clone(),
+                                            &root.name,
+                                        )
+                                        .on_hover_text(root.path.to_string_lossy());
+                                    }
+                                });
+                        });
+                        if let Some(current) = self.pending_import.as_mut() {
+                            current.target_root = pending.target_root.clone();
                         }
+                    }
 
+                    // An encrypted manifest may list server configs only the password reveals
+                    let has_server_config = pending.info.encrypted_manifest.is_some()
+                        || pending
+                            .info
+                            .manifest
+                            .as_ref()
+                            .is_some_and(|manifest| manifest.server_configs().next().is_some());
+                    let into_server = self.is_server_dir(&pending.target_root);
+                    if has_server_config && into_server {
+                        ui.add_space(5.0);
                         if ui
-                            .button(
-                                egui::RichText::new(t!("app.delete"))
-                                    .color(egui::Color32::from_rgb(255, 100, 100)),
+                            .checkbox(
+                                &mut pending.restore_server_config,
+                                t!("app.restore_server_config"),
                             )
-                            .clicked()
+                            .changed()
                         {
-                            if let Err(e) = fs::remove_file(&backup_path) {
-                                self.status_message = format!("{} {}", t!("app.error"), e);
-                            } else {
-                                self.status_message = t!("app.backup_deleted").to_string();
+                            if let Some(current) = self.pending_import.as_mut() {
+                                current.restore_server_config = pending.restore_server_config;
                             }
-                            self.pending_delete_backup = None;
                         }
-                    });
-                });
-        }
-    }
+                    }
 
-    fn render_import_dialog(&mut self, ctx: &egui::Context) {
-        if let Some((zip_path, world_name)) = self.pending_import.clone() {
-            egui::Window::new(t!("app.confirm_import_title"))
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .show(ctx, |ui| {
-                    ui.label(t!("app.confirm_import_message"));
-                    ui.add_space(10.0);
-                    ui.label(egui::RichText::new(&world_name).strong().size(16.0));
-                    ui.add_space(10.0);
-                    ui.label(
-                        egui::RichText::new(t!("app.confirm_import_warning"))
-                            .color(egui::Color32::from_rgb(255, 180, 100)),
-                    );
+                    if pending.info.encrypted {
+                        ui.add_space(10.0);
+                        ui.label(t!("app.import_password_hint"));
+                        ui.add(egui::TextEdit::singleline(&mut pending.password).password(true));
+                        if let Some(current) = self.pending_import.as_mut() {
+                            current.password = pending.password.clone();
+                        }
+                    }
                     ui.add_space(15.0);
 
                     ui.horizontal(|ui| {
@@ -150,22 +358,19 @@ impl HytaleBackupApp {
                             self.pending_import = None;
                         }
 
+                        let can_import = (!pending.info.encrypted || !pending.password.is_empty())
+                            && pending.info.signature != SignatureStatus::Tampered;
                         if ui
-                            .button(
-                                egui::RichText::new(t!("app.import"))
-                                    .color(egui::Color32::from_rgb(100, 200, 100)),
+                            .add_enabled(
+                                can_import,
+                                egui::Button::new(
+                                    egui::RichText::new(t!("app.import"))
+                                        .color(egui::Color32::from_rgb(100, 200, 100)),
+                                ),
                             )
                             .clicked()
                         {
-                            match import_world(&zip_path, &world_name) {
-                                Ok(_) => {
-                                    self.status_message = t!("app.import_success").to_string();
-                                    self.refresh_worlds();
-                                }
-                                Err(e) => {
-                                    self.status_message = format!("{} {}", t!("app.error"), e);
-                                }
-                            }
+                            self.start_import(ctx, pending.clone(), into_server);
                             self.pending_import = None;
                         }
                     });
@@ -176,176 +381,482 @@ impl HytaleBackupApp {
     fn render_toolbar(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         ui.add_space(10.0);
 
-        let progress_state = self.progress.lock().unwrap().clone();
-
-        if progress_state.is_running {
-            self.render_progress_bar(ui, &progress_state);
+        self.render_jobs(ui);
+        if self.jobs.running_count() > 0 {
             ctx.request_repaint();
-        } else {
-            if let Some(result) = progress_state.result.clone() {
-                self.status_message = match result {
-                    Ok(path) => format!("{}\n{}", t!("app.backup_success"), path),
-                    Err(e) => format!("{} {}", t!("app.error"), e),
-                };
-                self.progress.lock().unwrap().result = None;
-            }
-
-            self.render_toolbar_controls(ctx, ui);
         }
 
+        self.render_toolbar_controls(ctx, ui);
+
         if !self.status_message.is_empty() {
             ui.add_space(5.0);
             ui.separator();
             ui.label(&self.status_message);
+            self.render_trash_undo(ui);
         }
 
         ui.add_space(10.0);
     }
 
-    fn render_progress_bar(&self, ui: &mut egui::Ui, progress_state: &BackupProgress) {
-        ui.vertical_centered(|ui| {
-            ui.label(t!("app.compressing"));
-
-            let progress_fraction = if progress_state.total > 0 {
-                progress_state.current as f32 / progress_state.total as f32
-            } else {
-                0.0
-            };
-
-            ui.add(
-                egui::ProgressBar::new(progress_fraction)
-                    .show_percentage()
-                    .animate(true),
+    fn render_toolbar_controls(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.settings.include_logs, t!("app.include_logs"));
+            ui.add_space(20.0);
+            ui.checkbox(
+                &mut self.settings.include_backups,
+                t!("app.include_backups"),
             );
-
-            ui.label(format!(
-                "{} / {}",
-                progress_state.current, progress_state.total
-            ));
-
-            if !progress_state.current_file.is_empty() {
-                ui.label(&progress_state.current_file);
-            }
+            ui.add_space(20.0);
+            ui.checkbox(
+                &mut self.settings.verify_after_export,
+                t!("app.verify_after_export"),
+            );
+            ui.add_space(20.0);
+            ui.checkbox(&mut self.encrypt_export, t!("app.encrypt_export"));
+            ui.add_space(20.0);
+            ui.add_enabled_ui(self.signing_identity.is_some(), |ui| {
+                ui.checkbox(&mut self.sign_export, t!("app.sign_export"))
+                    .on_disabled_hover_text(t!("app.sign_export_no_key"));
+            });
         });
-    }
 
-    fn render_toolbar_controls(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            ui.checkbox(&mut self.include_logs, t!("app.include_logs"));
+            ui.checkbox(&mut self.settings.split_export, t!("app.split_export"));
+            ui.add_enabled(
+                self.settings.split_export,
+                egui::DragValue::new(&mut self.settings.split_size_mb)
+                    .range(1..=1_048_576)
+                    .suffix(" MB"),
+            );
             ui.add_space(20.0);
-            ui.checkbox(&mut self.include_backups, t!("app.include_backups"));
+            ui.checkbox(
+                &mut self.settings.snapshot_export,
+                t!("app.snapshot_export"),
+            )
+            .on_hover_text(t!("app.snapshot_export_hint"));
+
+            ui.add_space(20.0);
+            ui.checkbox(&mut self.settings.batch_bundle, t!("app.batch_bundle"))
+                .on_hover_text(t!("app.batch_bundle_hint"));
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                let button_enabled = self.selected_world.is_some();
+                let selection = self.export_selection();
+                if selection.len() > 1 {
+                    if ui
+                        .button(t!("app.export_selected", count = selection.len()))
+                        .clicked()
+                    {
+                        self.request_batch_export(ctx, selection);
+                    }
+                } else {
+                    let button_enabled = self.selected_world.is_some();
+                    if ui
+                        .add_enabled(button_enabled, egui::Button::new(t!("app.compress_world")))
+                        .clicked()
+                    {
+                        self.request_export(ctx);
+                    }
+                }
                 if ui
-                    .add_enabled(button_enabled, egui::Button::new(t!("app.compress_world")))
+                    .add_enabled(
+                        !self.worlds.is_empty(),
+                        egui::Button::new(t!("app.export_all")),
+                    )
                     .clicked()
                 {
-                    self.start_backup(ctx);
+                    self.request_batch_export(ctx, (0..self.worlds.len()).collect());
                 }
             });
         });
     }
 
-    fn start_backup(&mut self, ctx: &egui::Context) {
-        if let Some(index) = self.selected_world {
-            let world = self.worlds[index].clone();
-            let include_logs = self.include_logs;
-            let include_backups = self.include_backups;
+    /// Warns before exporting a world the game or a server may be writing to
+    fn request_export(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.selected_world else {
+            return;
+        };
 
-            let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
-            let default_filename = format!("{}_{}.zip", world.name, timestamp);
+        self.pending_batch = None;
+        let usage = detect_world_usage(&self.worlds[index].path);
+        if usage.is_in_use() {
+            self.pending_usage_warning = Some(usage);
+        } else {
+            self.continue_export(ctx);
+        }
+    }
 
-            let file_dialog = rfd::FileDialog::new()
-                .set_file_name(&default_filename)
-                .add_filter("ZIP", &["zip"]);
+    /// Worlds selected for export, in list order
+    fn export_selection(&self) -> Vec<usize> {
+        self.worlds
+            .iter()
+            .enumerate()
+            .filter(|(index, world)| {
+                self.selected_world == Some(*index) || self.selected_worlds.contains(&world.path)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
 
-            let file_dialog = if let Some(downloads) = dirs::download_dir() {
-                file_dialog.set_directory(&downloads)
-            } else {
-                file_dialog
-            };
+    /// Starts exporting several worlds, warning first if any of them is in use
+    fn request_batch_export(&mut self, ctx: &egui::Context, worlds: Vec<usize>) {
+        let paths: Vec<&Path> = worlds
+            .iter()
+            .map(|&index| self.worlds[index].path.as_path())
+            .collect();
+        let usage = detect_usage_of_worlds(&paths);
 
-            if let Some(save_path) = file_dialog.save_file() {
-                let progress = Arc::clone(&self.progress);
-                let ctx = ctx.clone();
+        self.pending_batch = Some(worlds);
+        if usage.is_in_use() {
+            self.pending_usage_warning = Some(usage);
+        } else {
+            self.continue_export(ctx);
+        }
+    }
 
-                {
-                    let mut p = progress.lock().unwrap();
-                    p.is_running = true;
-                    p.current = 0;
-                    p.total = 0;
-                    p.current_file = String::new();
-                    p.result = None;
+    fn continue_export(&mut self, ctx: &egui::Context) {
+        if self.encrypt_export {
+            self.pending_export_password = Some((String::new(), String::new()));
+        } else {
+            self.start_export(ctx, None);
+        }
+    }
+
+    fn start_export(&mut self, ctx: &egui::Context, password: Option<String>) {
+        match self.pending_batch.take() {
+            Some(worlds) => self.start_batch_export(ctx, worlds, password),
+            None => self.start_backup(ctx, password),
+        }
+    }
+
+    fn render_usage_warning_dialog(&mut self, ctx: &egui::Context) {
+        let Some(usage) = self.pending_usage_warning.clone() else {
+            return;
+        };
+
+        egui::Window::new(t!("app.world_in_use_title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(t!("app.world_in_use_message"));
+                ui.add_space(10.0);
+
+                for process in &usage.processes {
+                    ui.label(t!("app.running_process", name = process));
                 }
+                for lock_file in &usage.lock_files {
+                    ui.label(t!(
+                        "app.lock_file_found",
+                        path = lock_file.to_string_lossy()
+                    ));
+                }
+                ui.add_space(15.0);
 
-                thread::spawn(move || {
-                    let result = backup_world_to_path_with_progress(
-                        &world.name,
-                        &save_path,
-                        include_logs,
-                        include_backups,
-                        &progress,
-                        &ctx,
-                    );
+                ui.horizontal(|ui| {
+                    if ui.button(t!("app.cancel")).clicked() {
+                        self.pending_usage_warning = None;
+                        self.pending_batch = None;
+                    }
 
-                    let mut p = progress.lock().unwrap();
-                    p.is_running = false;
-                    p.result = Some(result);
-                    ctx.request_repaint();
+                    if ui
+                        .button(
+                            egui::RichText::new(t!("app.export_anyway"))
+                                .color(egui::Color32::from_rgb(255, 180, 100)),
+                        )
+                        .clicked()
+                    {
+                        self.pending_usage_warning = None;
+                        self.continue_export(ctx);
+                    }
                 });
-            }
-        }
+            });
     }
 
-    fn render_world_list(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            ui.label(t!("app.available_worlds"));
-            if ui.button(t!("app.refresh")).clicked() {
-                self.refresh_worlds();
-            }
-            if ui.button(t!("app.import_world")).clicked() {
-                self.handle_import_button();
-            }
-        });
+    fn render_export_password_dialog(&mut self, ctx: &egui::Context) {
+        if let Some((mut password, mut confirmation)) = self.pending_export_password.clone() {
+            egui::Window::new(t!("app.export_password_title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(t!("app.export_password_message"));
+                    ui.add_space(10.0);
 
-        ui.add_space(10.0);
+                    egui::Grid::new("export_password")
+                        .num_columns(2)
+                        .spacing([10.0, 5.0])
+                        .show(ui, |ui| {
+                            ui.label(t!("app.password"));
+                            ui.add(egui::TextEdit::singleline(&mut password).password(true));
+                            ui.end_row();
+
+                            ui.label(t!("app.password_confirm"));
+                            ui.add(egui::TextEdit::singleline(&mut confirmation).password(true));
+                            ui.end_row();
+                        });
+
+                    let matches = password == confirmation;
+                    if !matches && !confirmation.is_empty() {
+                        ui.add_space(5.0);
+                        ui.label(
+                            egui::RichText::new(t!("app.password_mismatch"))
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        );
+                    }
+                    ui.add_space(15.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button(t!("app.cancel")).clicked() {
+                            self.pending_export_password = None;
+                            self.pending_batch = None;
+                            return;
+                        }
 
-        if self.worlds.is_empty() {
-            ui.label(t!("app.no_worlds_found"));
-        } else {
-            egui::ScrollArea::vertical()
-                .id_salt("worlds_list")
-                .max_height(120.0)
-                .show(ui, |ui| {
-                    for (index, world) in self.worlds.iter().enumerate() {
-                        let is_selected = self.selected_world == Some(index);
                         if ui
-                            .selectable_label(is_selected, format!("🌍 {}", world.name))
+                            .add_enabled(
+                                matches && !password.is_empty(),
+                                egui::Button::new(t!("app.compress_world")),
+                            )
                             .clicked()
                         {
-                            self.selected_world = Some(index);
+                            self.pending_export_password = None;
+                            self.start_export(ctx, Some(password.clone()));
+                            return;
                         }
-                    }
+
+                        self.pending_export_password =
+                            Some((password.clone(), confirmation.clone()));
+                    });
                 });
         }
     }
 
+    /// Export options from the toolbar and settings for a world
+    fn export_options(&self, world: &WorldInfo, password: Option<String>) -> ExportOptions {
+        let sign = self.sign_export && self.signing_identity.is_some();
+        self.settings.export_options(world, password, sign)
+    }
+
+    /// Folder the export dialogs open in
+    fn export_directory(&self) -> Option<PathBuf> {
+        self.settings
+            .default_export_dir
+            .clone()
+            .or_else(|| self.settings.last_export_dir.clone())
+            .or_else(dirs::download_dir)
+    }
+
+    /// Renders the filename template for a world
+    fn export_filename(
+        &self,
+        world: &WorldInfo,
+        directory: Option<&Path>,
+    ) -> Result<String, String> {
+        // Measuring a world that is still being scanned would block the window
+        self.settings.archive_file_name(world, directory, false)
+    }
+
+    fn start_backup(&mut self, ctx: &egui::Context, password: Option<String>) {
+        if let Some(index) = self.selected_world {
+            let world = self.worlds[index].clone();
+            let options = self.export_options(&world, password);
+            let directory = self.export_directory();
+            let default_filename = match self.export_filename(&world, directory.as_deref()) {
+                Ok(filename) => filename,
+                Err(e) => {
+                    self.status_message = format!("{} {}", t!("app.error"), e);
+                    return;
+                }
+            };
+
+            let file_dialog = rfd::FileDialog::new()
+                .set_file_name(&default_filename)
+                .add_filter("ZIP", &["zip"]);
+            let file_dialog = if let Some(directory) = directory {
+                file_dialog.set_directory(&directory)
+            } else {
+                file_dialog
+            };
+
+            if let Some(save_path) = file_dialog.save_file() {
+                self.settings.last_export_dir = save_path.parent().map(Path::to_path_buf);
+                let settings = self.settings.clone();
+                self.jobs.push(
+                    JobKind::Export,
+                    world.name.clone(),
+                    vec![world.path.clone()],
+                    move |progress, ctx| {
+                        let export = backup_world_to_path_with_progress(
+                            &world.path,
+                            &save_path,
+                            &options,
+                            progress,
+                            ctx,
+                        )?;
+                        let mut report = JobReport::from_export(&export);
+                        prune_after_export(&settings, &world.path, &export, &mut report);
+                        Ok(report)
+                    },
+                    ctx,
+                );
+            }
+        }
+    }
+
+    /// Exports several worlds into a folder chosen by the user
+    fn start_batch_export(
+        &mut self,
+        ctx: &egui::Context,
+        worlds: Vec<usize>,
+        password: Option<String>,
+    ) {
+        let mut file_dialog = rfd::FileDialog::new();
+        if let Some(directory) = self.export_directory() {
+            file_dialog = file_dialog.set_directory(directory);
+        }
+        let Some(directory) = file_dialog.pick_folder() else {
+            return;
+        };
+
+        let mut jobs: Vec<BatchJob> = Vec::new();
+        for index in worlds {
+            let world = &self.worlds[index];
+            let file_name = match self.export_filename(world, Some(&directory)) {
+                Ok(file_name) => file_name,
+                Err(e) => {
+                    self.status_message = format!("{} {}", t!("app.error"), e);
+                    return;
+                }
+            };
+            // Worlds of the same name from different roots must not overwrite each
+            // other, nor existing archives; inside a bundle only the names matter
+            let taken: Vec<&str> = jobs.iter().map(|job| job.file_name.as_str()).collect();
+            let existing = (!self.settings.batch_bundle).then_some(directory.as_path());
+            let file_name = unique_file_name(&file_name, existing, &taken);
+            jobs.push(BatchJob {
+                world_name: world.name.clone(),
+                world_path: world.path.clone(),
+                file_name,
+                options: self.export_options(world, password.clone()),
+            });
+        }
+
+        let bundle_name = if self.settings.batch_bundle {
+            let hostname = sysinfo::System::host_name().unwrap_or_default();
+            let context = TemplateContext {
+                world: "worlds",
+                time: chrono::Local::now(),
+                hostname: &hostname,
+                size: Some(0),
+                tags: &self.settings.export_tags,
+            };
+            match render_unique(
+                &self.settings.filename_template,
+                &context,
+                Some(&directory),
+                "zip",
+            ) {
+                Ok(name) => Some(unique_file_name(&name, Some(&directory), &[])),
+                Err(e) => {
+                    self.status_message = format!("{} {}", t!("app.error"), e);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+
+        self.settings.last_export_dir = Some(directory.clone());
+        let label = t!("app.batch_job", count = jobs.len()).to_string();
+        let settings = self.settings.clone();
+        let world_paths = jobs.iter().map(|job| job.world_path.clone()).collect();
+        self.jobs.push(
+            JobKind::Export,
+            label,
+            world_paths,
+            move |progress, ctx| {
+                let results =
+                    export_batch(&jobs, &directory, bundle_name.as_deref(), progress, ctx)?;
+                let destination = match &bundle_name {
+                    Some(name) => directory.join(name),
+                    None => directory,
+                };
+                let mut report =
+                    jobs::batch_job_report(&results, destination, bundle_name.is_some())?;
+                // Archives inside a bundle are not subject to retention rules
+                if bundle_name.is_none() {
+                    jobs::prune_after_batch(&settings, &jobs, &results, &mut report);
+                }
+                Ok(report)
+            },
+            ctx,
+        );
+    }
+
+    /// Queues the import of a confirmed archive
+    fn start_import(&mut self, ctx: &egui::Context, pending: PendingImport, into_server: bool) {
+        let target = pending.target_root.join(&pending.world_name);
+        self.jobs.push(
+            JobKind::Import,
+            pending.world_name.clone(),
+            vec![target.clone()],
+            move |_, _| {
+                let password = pending.info.encrypted.then_some(pending.password.as_str());
+                let server_dir = (into_server && pending.restore_server_config)
+                    .then_some(pending.target_root.as_path());
+                import_world(&pending.zip_path, &target, password, server_dir)?;
+                Ok(JobReport {
+                    size: Some(get_directory_size(&target)),
+                    destination: target,
+                    message: t!("app.import_success").to_string(),
+                    warning: None,
+                })
+            },
+            ctx,
+        );
+    }
+
     fn handle_import_button(&mut self) {
-        let file_dialog = rfd::FileDialog::new().add_filter("ZIP", &["zip"]);
+        let file_dialog = rfd::FileDialog::new().add_filter("ZIP", &["zip", "001"]);
 
         if let Some(zip_path) = file_dialog.pick_file() {
-            if zip_path.extension().map_or(false, |ext| ext == "zip") {
-                if let Some(filename) = zip_path.file_stem() {
+            // For split archives the world name comes from "<name>.zip.001"
+            let archive_name = if is_first_volume(&zip_path) {
+                zip_path.with_extension("")
+            } else {
+                zip_path.clone()
+            };
+
+            if is_volume(&zip_path) && !is_first_volume(&zip_path) {
+                self.status_message = t!("app.error_not_first_volume").to_string();
+            } else if archive_name.extension().is_some_and(|ext| ext == "zip") {
+                if let Some(filename) = archive_name.file_stem() {
                     let filename_str = filename.to_string_lossy().to_string();
-                    let world_name =
-                        if filename_str.len() > 20 && filename_str.chars().rev().nth(19) == Some('_')
-                        {
-                            filename_str[..filename_str.len() - 20].to_string()
-                        } else {
-                            filename_str
-                        };
-                    self.pending_import = Some((zip_path, world_name));
+                    let Some(target_root) = self.import_targets().into_iter().next() else {
+                        self.status_message = t!("app.error_no_save_roots").to_string();
+                        return;
+                    };
+                    match inspect_archive(&zip_path) {
+                        Ok(info) => {
+                            let Some(world_name) = import_world_name(&info, &filename_str) else {
+                                self.status_message =
+                                    t!("errors.invalid_world_name", name = filename_str)
+                                        .to_string();
+                                return;
+                            };
+                            self.pending_import = Some(PendingImport {
+                                zip_path,
+                                world_name,
+                                info,
+                                password: String::new(),
+                                target_root: target_root.path,
+                                restore_server_config: true,
+                            });
+                        }
+                        Err(e) => {
+                            self.status_message = format!("{} {}", t!("app.error"), e);
+                        }
+                    }
                 }
             } else {
                 self.status_message = t!("app.error_not_zip").to_string();
@@ -373,16 +884,30 @@ impl HytaleBackupApp {
                             .spacing([10.0, 5.0])
                             .show(ui, |ui| {
                                 ui.label(t!("app.detail_size"));
-                                ui.label(format_size(world.size));
+                                match &world.details {
+                                    Some(details) => ui.label(format_size(details.size)),
+                                    None => {
+                                        ui.label(egui::RichText::new(t!("app.calculating")).weak())
+                                    }
+                                };
                                 ui.end_row();
 
                                 ui.label(t!("app.detail_last_played"));
-                                ui.label(
-                                    world
-                                        .last_played
-                                        .clone()
-                                        .unwrap_or_else(|| t!("app.unknown").to_string()),
-                                );
+                                match &world.details {
+                                    Some(details) => ui.label(
+                                        details
+                                            .last_played
+                                            .clone()
+                                            .unwrap_or_else(|| t!("app.unknown").to_string()),
+                                    ),
+                                    None => {
+                                        ui.label(egui::RichText::new(t!("app.calculating")).weak())
+                                    }
+                                };
+                                ui.end_row();
+
+                                ui.label(t!("app.detail_source"));
+                                ui.label(&world.source);
                                 ui.end_row();
 
                                 ui.label(t!("app.detail_path"));
@@ -395,87 +920,78 @@ impl HytaleBackupApp {
                             });
                     });
 
+                let world_path = world.path.clone();
+                ui.add_space(10.0);
+                self.render_schedule(ui, &world_path);
+                self.render_retention(ui, &world_path);
+
                 ui.add_space(15.0);
-                self.render_tabs(ui, &world.path.clone());
+                self.render_tabs(ui, Some(&world_path));
             }
         } else {
             ui.label(t!("app.select_world_hint"));
+            ui.add_space(15.0);
+            self.render_tabs(ui, None);
         }
     }
 
-    fn render_tabs(&mut self, ui: &mut egui::Ui, world_path: &PathBuf) {
+    /// Tabs below the world details; only the history is shown without a world
+    fn render_tabs(&mut self, ui: &mut egui::Ui, world_path: Option<&Path>) {
         ui.horizontal(|ui| {
             if ui
-                .selectable_label(self.selected_tab == 0, t!("app.tab_backups"))
+                .selectable_label(self.settings.selected_tab == 0, t!("app.tab_backups"))
+                .clicked()
+            {
+                self.settings.selected_tab = 0;
+            }
+            ui.separator();
+            if ui
+                .selectable_label(self.settings.selected_tab == 1, t!("app.tab_logs"))
                 .clicked()
             {
-                self.selected_tab = 0;
+                self.settings.selected_tab = 1;
             }
             ui.separator();
             if ui
-                .selectable_label(self.selected_tab == 1, t!("app.tab_logs"))
+                .selectable_label(self.settings.selected_tab == 2, t!("app.tab_history"))
                 .clicked()
             {
-                self.selected_tab = 1;
+                self.settings.selected_tab = 2;
             }
         });
 
         ui.add_space(10.0);
 
-        match self.selected_tab {
-            0 => self.render_backups_tab(ui, world_path),
-            1 => self.render_logs_tab(ui, world_path),
+        if self.settings.selected_tab == 2 {
+            self.render_history_tab(ui);
+            return;
+        }
+        let Some(world_path) = world_path else {
+            return;
+        };
+        let view = self.take_world_view(world_path);
+        match self.settings.selected_tab {
+            0 => self.render_backups_tab(ui, &view.backups),
+            1 => self.render_logs_tab(ui, view.log.as_ref()),
             _ => {}
         }
+        self.world_view = Some(view);
     }
 
-    fn render_backups_tab(&mut self, ui: &mut egui::Ui, world_path: &PathBuf) {
-        let backups = get_world_backups(world_path);
-
-        if backups.is_empty() {
-            ui.label(t!("app.no_backups_found"));
-        } else {
-            egui::ScrollArea::vertical()
-                .id_salt("backups_list")
-                .max_height(TAB_CONTENT_MAX_HEIGHT)
-                .show(ui, |ui| {
-                    for backup in &backups {
-                        egui::Frame::group(ui.style()).inner_margin(5.0).show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.label(egui::RichText::new(&backup.name).strong());
-                                    ui.label(egui::RichText::new(format_size(backup.size)).weak());
-                                });
-
-                                ui.with_layout(
-                                    egui::Layout::right_to_left(egui::Align::Center),
-                                    |ui| {
-                                        if ui
-                                            .button("🗑")
-                                            .on_hover_text(t!("app.delete_backup"))
-                                            .clicked()
-                                        {
-                                            self.pending_delete_backup = Some(backup.path.clone());
-                                        }
-                                        if ui
-                                            .button("📂")
-                                            .on_hover_text(t!("app.open_in_finder"))
-                                            .clicked()
-                                        {
-                                            open_file_in_finder(&backup.path);
-                                        }
-                                    },
-                                );
-                            });
-                        });
-                        ui.add_space(5.0);
-                    }
-                });
+    /// Returns the cached view of a world, reading it from disk if needed
+    fn take_world_view(&mut self, world_path: &Path) -> WorldView {
+        match self.world_view.take() {
+            Some(view) if view.path == world_path => view,
+            _ => WorldView {
+                path: world_path.to_path_buf(),
+                backups: get_world_backups(world_path),
+                log: get_latest_log(world_path),
+            },
         }
     }
 
-    fn render_logs_tab(&mut self, ui: &mut egui::Ui, world_path: &PathBuf) {
-        if let Some(log) = get_latest_log(world_path) {
+    fn render_logs_tab(&mut self, ui: &mut egui::Ui, log: Option<&LogInfo>) {
+        if let Some(log) = log {
             ui.horizontal(|ui| {
                 ui.label(egui::RichText::new(&log.name).strong());
                 if ui
@@ -487,10 +1003,10 @@ impl HytaleBackupApp {
                 }
                 ui.separator();
                 if ui
-                    .selectable_label(self.hide_info_logs, t!("app.filter_errors_only"))
+                    .selectable_label(self.settings.hide_info_logs, t!("app.filter_errors_only"))
                     .clicked()
                 {
-                    self.hide_info_logs = !self.hide_info_logs;
+                    self.settings.hide_info_logs = !self.settings.hide_info_logs;
                 }
             });
             ui.add_space(5.0);
@@ -501,7 +1017,7 @@ impl HytaleBackupApp {
                 .show(ui, |ui| {
                     for line in log.content.lines() {
                         // Filter lines using LogFilter module
-                        if should_filter_line(line, self.hide_info_logs) {
+                        if should_filter_line(line, self.settings.hide_info_logs) {
                             continue;
                         }
 
@@ -524,10 +1040,30 @@ impl HytaleBackupApp {
 }
 
 impl eframe::App for HytaleBackupApp {
+    /// Called by eframe periodically and on shutdown
+    fn save(&mut self, _storage: &mut dyn eframe::Storage) {
+        let _ = save_settings(&self.settings);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.apply_file_changes(ctx);
+        self.apply_scan_results();
+        self.jobs
+            .set_max_running(self.settings.max_parallel_jobs, ctx);
+        self.apply_finished_jobs(ctx);
+        self.apply_trash_results();
+        self.run_schedules(ctx);
+        self.run_session_backups(ctx);
+
         // Render dialogs
         self.render_delete_dialog(ctx);
+        self.render_restore_dialog(ctx);
         self.render_import_dialog(ctx);
+        self.render_usage_warning_dialog(ctx);
+        self.render_export_password_dialog(ctx);
+        self.render_verify_dialog(ctx);
+        self.render_key_manager(ctx);
+        self.render_settings_window(ctx);
 
         // Bottom toolbar
         egui::TopBottomPanel::bottom("toolbar").show(ctx, |ui| {
@@ -555,3 +1091,106 @@ impl eframe::App for HytaleBackupApp {
     }
 }
 
+/// Picks the folder name for an imported world
+///
+/// Archives with a manifest carry the original world name. Older archives only
+/// have their file name, from which the default `_<timestamp>` suffix is cut.
+/// `None` if neither can be used as a folder name.
+fn import_world_name(info: &ArchiveInfo, file_stem: &str) -> Option<String> {
+    if let Some(manifest) = &info.manifest {
+        if let Some(name) = folder_name(&manifest.world_name) {
+            return Some(name.to_string());
+        }
+    }
+
+    // The timestamp holds an underscore of its own
+    let name = file_stem
+        .rsplit_once('_')
+        .and_then(|(rest, _)| rest.rsplit_once('_'))
+        .filter(|(name, _)| {
+            let suffix = &file_stem[name.len() + 1..];
+            NaiveDateTime::parse_from_str(suffix, "%Y-%m-%d_%H-%M-%S").is_ok()
+        })
+        .map_or(file_stem, |(name, _)| name);
+    folder_name(name).map(str::to_string)
+}
+
+/// The trimmed name, if it can be used as the folder of a world
+fn folder_name(name: &str) -> Option<&str> {
+    let name = name.trim();
+    // The name becomes a folder, so it must not lead out of the save root.
+    // Hidden names are taken by the import and snapshot staging folders.
+    let mut components = Path::new(name).components();
+    let single_folder = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    (single_folder && !name.starts_with('.') && !name.contains(['/', '\\'])).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::ExportManifest;
+
+    #[test]
+    fn test_import_world_name() {
+        let info = |world_name: &str| ArchiveInfo {
+            encrypted: false,
+            manifest: Some(ExportManifest::new(world_name)),
+            encrypted_manifest: None,
+            signature: SignatureStatus::Unsigned,
+        };
+        let stem = "Fallback_2026-01-13_19-35-06";
+
+        assert_eq!(
+            import_world_name(&info("My World"), stem).as_deref(),
+            Some("My World")
+        );
+        for world_name in [
+            "",
+            "..",
+            "../World",
+            "a/b",
+            "a\\b",
+            ".World.importing",
+            "/World",
+        ] {
+            assert_eq!(
+                import_world_name(&info(world_name), stem).as_deref(),
+                Some("Fallback")
+            );
+        }
+
+        // Without a manifest only a timestamp of the old default name is cut
+        let plain = ArchiveInfo {
+            encrypted: false,
+            manifest: None,
+            encrypted_manifest: None,
+            signature: SignatureStatus::Unsigned,
+        };
+        assert_eq!(import_world_name(&plain, stem).as_deref(), Some("Fallback"));
+        assert_eq!(
+            import_world_name(&plain, "My_World").as_deref(),
+            Some("My_World")
+        );
+        assert_eq!(import_world_name(&plain, "a_b_c").as_deref(), Some("a_b_c"));
+        let multibyte = format!("world_{}", "€".repeat(19));
+        assert_eq!(
+            import_world_name(&plain, &multibyte),
+            Some(multibyte.clone())
+        );
+        assert_eq!(
+            import_world_name(&plain, "Welt€_2026-01-13_19-35-06").as_deref(),
+            Some("Welt€")
+        );
+        assert_eq!(import_world_name(&plain, ".foo"), None);
+        assert_eq!(import_world_name(&plain, ".."), None);
+        assert_eq!(import_world_name(&plain, ".foo_2026-01-13_19-35-06"), None);
+        #[cfg(windows)]
+        assert_eq!(
+            import_world_name(&info("C:evil"), stem).as_deref(),
+            Some("Fallback")
+        );
+    }
+}
@@ -0,0 +1,663 @@
+use chrono::{DateTime, Local, NaiveDate};
+use eframe::egui;
+use rust_i18n::t;
+use std::path::{Path, PathBuf};
+
+use super::{HytaleBackupApp, TAB_CONTENT_MAX_HEIGHT};
+use crate::consistency::detect_world_usage;
+use crate::game_backup::{
+    backups_beyond_newest, backups_older_than, inspect_game_backup, restore_game_backup,
+    restore_plan,
+};
+use crate::jobs::JobReport;
+use crate::models::{
+    ArchiveDetails, BackupInfo, BackupSort, BackupSortColumn, JobKind, PendingRestore,
+};
+use crate::recycle::{TrashOutcome, TrashedFile, LOCAL_TRASH_DAYS};
+use crate::utils::{format_size, get_directory_size, open_file_in_finder};
+
+/// Height of the list of backups in the delete confirmation
+const DELETE_LIST_MAX_HEIGHT: f32 = 150.0;
+
+/// Width at which the lists of the restore confirmation wrap
+const RESTORE_DIALOG_WIDTH: f32 = 420.0;
+
+impl HytaleBackupApp {
+    /// In-game backups of the selected world, with bulk selection for cleaning up
+    pub(super) fn render_backups_tab(&mut self, ui: &mut egui::Ui, backups: &[BackupInfo]) {
+        if backups.is_empty() {
+            ui.label(t!("app.no_backups_found"));
+            return;
+        }
+        // Selections of another world or of deleted backups do not carry over
+        self.selected_backups
+            .retain(|path| backups.iter().any(|backup| backup.path == *path));
+
+        self.render_backup_selection(ui, backups);
+        self.render_backup_sorting(ui);
+        ui.add_space(5.0);
+
+        let by_day = self.settings.group_backups_by_day;
+        let groups = group_backups(backups, self.settings.backup_sort, by_day);
+        egui::ScrollArea::vertical()
+            .id_salt("backups_list")
+            .max_height(TAB_CONTENT_MAX_HEIGHT)
+            .show(ui, |ui| {
+                for group in &groups {
+                    if by_day {
+                        let day = group.day.map_or_else(
+                            || t!("app.unknown_day").to_string(),
+                            |day| day.format("%Y-%m-%d").to_string(),
+                        );
+                        let size = group.backups.iter().map(|backup| backup.size).sum();
+                        ui.label(
+                            egui::RichText::new(t!(
+                                "app.backup_day",
+                                day = day,
+                                count = group.backups.len(),
+                                size = format_size(size)
+                            ))
+                            .strong(),
+                        );
+                        ui.add_space(3.0);
+                    }
+                    for backup in &group.backups {
+                        self.render_backup_row(ui, backup);
+                        ui.add_space(5.0);
+                    }
+                }
+            });
+    }
+
+    fn render_backup_row(&mut self, ui: &mut egui::Ui, backup: &BackupInfo) {
+        egui::Frame::group(ui.style())
+            .inner_margin(5.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let mut selected = self.selected_backups.contains(&backup.path);
+                    if ui.checkbox(&mut selected, "").changed() {
+                        if selected {
+                            self.selected_backups.insert(backup.path.clone());
+                        } else {
+                            self.selected_backups.remove(&backup.path);
+                        }
+                    }
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new(&backup.name).strong())
+                            .on_hover_text(backup_times(backup));
+                        let details = self.archive_scanner.details(backup, ui.ctx());
+                        ui.label(egui::RichText::new(backup_summary(backup, details)).weak());
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .button("🗑")
+                            .on_hover_text(t!("app.delete_backup"))
+                            .clicked()
+                        {
+                            self.pending_delete_backups = Some(vec![backup.clone()]);
+                        }
+                        if ui
+                            .button("📂")
+                            .on_hover_text(t!("app.open_in_finder"))
+                            .clicked()
+                        {
+                            open_file_in_finder(&backup.path);
+                        }
+                        if ui
+                            .button(t!("app.restore"))
+                            .on_hover_text(t!("app.restore_hint"))
+                            .clicked()
+                        {
+                            self.request_restore(backup);
+                        }
+                    });
+                });
+            });
+    }
+
+    /// Sort order of the backups and whether they are grouped by day
+    fn render_backup_sorting(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("app.sort_by"));
+            let columns = [
+                (BackupSortColumn::Time, t!("app.sort_time")),
+                (BackupSortColumn::Name, t!("app.column_name")),
+                (BackupSortColumn::Size, t!("app.column_size")),
+            ];
+            let sort = &mut self.settings.backup_sort;
+            for (column, label) in columns {
+                let active = sort.column == column;
+                let arrow = match (active, sort.ascending) {
+                    (false, _) => "",
+                    (true, true) => " ⬆",
+                    (true, false) => " ⬇",
+                };
+                if ui
+                    .selectable_label(active, format!("{}{}", label, arrow))
+                    .clicked()
+                {
+                    if active {
+                        sort.ascending = !sort.ascending;
+                    } else {
+                        // Newest and biggest backups are the interesting ones
+                        *sort = BackupSort {
+                            column,
+                            ascending: column == BackupSortColumn::Name,
+                        };
+                    }
+                }
+            }
+            ui.separator();
+            ui.checkbox(
+                &mut self.settings.group_backups_by_day,
+                t!("app.group_by_day"),
+            );
+        });
+    }
+
+    /// Size summary and the rules that select backups for deletion
+    fn render_backup_selection(&mut self, ui: &mut egui::Ui, backups: &[BackupInfo]) {
+        let total: u64 = backups.iter().map(|backup| backup.size).sum();
+        let selected: Vec<&BackupInfo> = backups
+            .iter()
+            .filter(|backup| self.selected_backups.contains(&backup.path))
+            .collect();
+        let selected_size: u64 = selected.iter().map(|backup| backup.size).sum();
+
+        ui.horizontal(|ui| {
+            ui.label(t!(
+                "app.backups_summary",
+                count = backups.len(),
+                size = format_size(total)
+            ));
+            if !selected.is_empty() {
+                ui.label(
+                    egui::RichText::new(t!(
+                        "app.backups_selected",
+                        count = selected.len(),
+                        size = format_size(selected_size)
+                    ))
+                    .weak(),
+                );
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(
+                        !selected.is_empty(),
+                        egui::Button::new(
+                            egui::RichText::new(t!("app.delete_selected"))
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        ),
+                    )
+                    .clicked()
+                {
+                    self.pending_delete_backups =
+                        Some(selected.iter().map(|backup| (*backup).clone()).collect());
+                }
+                if ui.button(t!("app.select_none")).clicked() {
+                    self.selected_backups.clear();
+                }
+                if ui.button(t!("app.select_all")).clicked() {
+                    self.selected_backups
+                        .extend(backups.iter().map(|backup| backup.path.clone()));
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(t!("app.select_older_than"));
+            ui.add(
+                egui::DragValue::new(&mut self.backup_cleanup_days)
+                    .range(1..=3650)
+                    .suffix(t!("app.days_suffix")),
+            );
+            if ui.button(t!("app.select")).clicked() {
+                let cutoff = Local::now() - chrono::Duration::days(self.backup_cleanup_days as i64);
+                self.selected_backups = backups_older_than(backups, cutoff).into_iter().collect();
+            }
+            ui.separator();
+            ui.label(t!("app.select_all_but_newest"));
+            ui.add(egui::DragValue::new(&mut self.backup_keep_newest).range(0..=1000));
+            if ui.button(t!("app.select")).clicked() {
+                self.selected_backups = backups_beyond_newest(backups, self.backup_keep_newest)
+                    .into_iter()
+                    .collect();
+            }
+        });
+    }
+
+    pub(super) fn render_delete_dialog(&mut self, ctx: &egui::Context) {
+        let Some(backups) = self.pending_delete_backups.clone() else {
+            return;
+        };
+        egui::Window::new(t!("app.confirm_delete_title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if backups.len() == 1 {
+                    ui.label(t!("app.confirm_delete_message"));
+                } else {
+                    let size = backups.iter().map(|backup| backup.size).sum();
+                    ui.label(t!(
+                        "app.confirm_delete_many_message",
+                        count = backups.len(),
+                        size = format_size(size)
+                    ));
+                }
+                ui.add_space(10.0);
+                egui::ScrollArea::vertical()
+                    .id_salt("delete_backups_list")
+                    .max_height(DELETE_LIST_MAX_HEIGHT)
+                    .show(ui, |ui| {
+                        for backup in &backups {
+                            ui.label(egui::RichText::new(&backup.name).strong());
+                        }
+                    });
+                ui.add_space(15.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button(t!("app.cancel")).clicked() {
+                        self.pending_delete_backups = None;
+                    }
+
+                    if ui
+                        .button(
+                            egui::RichText::new(t!("app.delete"))
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        )
+                        .clicked()
+                    {
+                        self.delete_backups(ctx, &backups);
+                        self.pending_delete_backups = None;
+                    }
+                });
+            });
+    }
+
+    /// Moves in-game backups to the trash in the background
+    fn delete_backups(&mut self, ctx: &egui::Context, backups: &[BackupInfo]) {
+        let paths: Vec<PathBuf> = backups.iter().map(|backup| backup.path.clone()).collect();
+        self.status_message = t!("app.moving_to_trash", count = paths.len()).to_string();
+        self.trash_undo = None;
+        self.trash.trash(paths, ctx);
+    }
+
+    /// Shows the results of finished moves to and from the trash
+    pub(super) fn apply_trash_results(&mut self) {
+        for outcome in self.trash.take_finished() {
+            self.status_message = match outcome {
+                TrashOutcome::Trashed(trashed, error) => {
+                    for file in &trashed {
+                        self.selected_backups.remove(&file.original);
+                    }
+                    let message = match error {
+                        Some(e) => format!("{} {}", t!("app.error"), e),
+                        None if trashed.iter().any(TrashedFile::in_local_trash) => t!(
+                            "app.backups_in_app_trash",
+                            count = trashed.len(),
+                            days = LOCAL_TRASH_DAYS
+                        )
+                        .to_string(),
+                        None if trashed.len() == 1 => t!("app.backup_deleted").to_string(),
+                        None => t!("app.backups_deleted", count = trashed.len()).to_string(),
+                    };
+                    self.trash_undo = (!trashed.is_empty()).then(|| (message.clone(), trashed));
+                    message
+                }
+                TrashOutcome::Restored(restored, error) => match error {
+                    Some(e) => format!("{} {}", t!("app.error"), e),
+                    None => t!("app.backups_restored_from_trash", count = restored).to_string(),
+                },
+            };
+            self.world_view = None;
+        }
+    }
+
+    /// Offers to undo the last deletion while its status message is shown
+    pub(super) fn render_trash_undo(&mut self, ui: &mut egui::Ui) {
+        let Some((message, files)) = &self.trash_undo else {
+            return;
+        };
+        // Undoing while files are still being moved could race with the move
+        if *message != self.status_message
+            || !files.iter().all(TrashedFile::can_restore)
+            || self.trash.is_busy()
+        {
+            return;
+        }
+        if !ui.button(t!("app.undo")).clicked() {
+            return;
+        }
+
+        let files = files.clone();
+        self.status_message = t!("app.restoring_from_trash", count = files.len()).to_string();
+        self.trash_undo = None;
+        self.trash.restore(files, ui.ctx());
+    }
+
+    /// Reads an in-game backup and asks for confirmation before restoring it
+    fn request_restore(&mut self, backup: &BackupInfo) {
+        let Some(world_path) = backup.path.parent().and_then(Path::parent) else {
+            return;
+        };
+        let world_name = world_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        match inspect_game_backup(&backup.path, &world_name) {
+            Ok(info) => {
+                self.pending_restore = Some(PendingRestore {
+                    backup: backup.clone(),
+                    world_path: world_path.to_path_buf(),
+                    plan: restore_plan(world_path, &info),
+                    in_use: detect_world_usage(world_path).is_in_use(),
+                    info,
+                    as_new_world: false,
+                    new_world_name: t!("app.restored_world_name", name = world_name).to_string(),
+                });
+            }
+            Err(e) => {
+                self.status_message = format!("{} {}", t!("app.error"), e);
+            }
+        }
+    }
+
+    pub(super) fn render_restore_dialog(&mut self, ctx: &egui::Context) {
+        let Some(mut pending) = self.pending_restore.clone() else {
+            return;
+        };
+        let target = restore_target(&pending);
+        let target_exists = pending.as_new_world && target.exists();
+        let name = pending.new_world_name.trim();
+        // Hidden folders are not listed as worlds
+        let valid_name = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+
+        egui::Window::new(t!("app.confirm_restore_title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.set_max_width(RESTORE_DIALOG_WIDTH);
+                ui.label(t!(
+                    "app.confirm_restore_message",
+                    count = pending.info.entry_count
+                ));
+                ui.add_space(10.0);
+                ui.label(egui::RichText::new(&pending.backup.name).strong());
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut pending.as_new_world, false, t!("app.restore_in_place"));
+                    ui.radio_value(&mut pending.as_new_world, true, t!("app.restore_as_new"));
+                });
+                if pending.as_new_world {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut pending.new_world_name)
+                            .desired_width(RESTORE_DIALOG_WIDTH),
+                    );
+                    if target_exists {
+                        ui.label(
+                            egui::RichText::new(t!("errors.world_exists"))
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        );
+                    }
+                } else {
+                    let plan = &pending.plan;
+                    let lists = [
+                        (t!("app.restore_replaced"), &plan.replaced),
+                        (t!("app.restore_removed"), &plan.removed),
+                        (t!("app.restore_added"), &plan.added),
+                        (t!("app.restore_kept"), &plan.kept),
+                    ];
+                    for (label, names) in lists {
+                        if !names.is_empty() {
+                            ui.label(format!("{} {}", label, names.join(", ")));
+                        }
+                    }
+                    ui.add_space(5.0);
+                    ui.label(
+                        egui::RichText::new(t!("app.confirm_restore_warning"))
+                            .color(egui::Color32::from_rgb(255, 180, 100)),
+                    );
+                    if pending.in_use {
+                        ui.label(
+                            egui::RichText::new(t!("app.world_in_use_message"))
+                                .color(egui::Color32::from_rgb(255, 180, 100)),
+                        );
+                    }
+                }
+                ui.add_space(15.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button(t!("app.cancel")).clicked() {
+                        self.pending_restore = None;
+                        return;
+                    }
+
+                    let can_restore = !pending.as_new_world || (valid_name && !target_exists);
+                    if ui
+                        .add_enabled(
+                            can_restore,
+                            egui::Button::new(
+                                egui::RichText::new(t!("app.restore"))
+                                    .color(egui::Color32::from_rgb(100, 200, 100)),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        self.start_restore(ctx, pending.clone(), target.clone());
+                        self.pending_restore = None;
+                        return;
+                    }
+                    if let Some(current) = self.pending_restore.as_mut() {
+                        current.as_new_world = pending.as_new_world;
+                        current.new_world_name = pending.new_world_name.clone();
+                    }
+                });
+            });
+    }
+
+    fn start_restore(&mut self, ctx: &egui::Context, pending: PendingRestore, target: PathBuf) {
+        let label = target
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        self.jobs.push(
+            JobKind::Import,
+            label,
+            vec![target.clone()],
+            move |_, _| {
+                restore_game_backup(&pending.backup.path, &target, &pending.info)?;
+                Ok(JobReport {
+                    size: Some(get_directory_size(&target)),
+                    destination: target,
+                    message: t!("app.restore_success").to_string(),
+                    warning: None,
+                })
+            },
+            ctx,
+        );
+    }
+}
+
+/// World folder a pending restore writes to
+fn restore_target(pending: &PendingRestore) -> PathBuf {
+    if pending.as_new_world {
+        pending
+            .world_path
+            .with_file_name(pending.new_world_name.trim())
+    } else {
+        pending.world_path.clone()
+    }
+}
+
+/// In-game backups under one heading of the backups tab
+struct BackupGroup<'a> {
+    /// Day the backups were made, if it is known
+    day: Option<NaiveDate>,
+    backups: Vec<&'a BackupInfo>,
+}
+
+/// Sorts the backups, into groups by day or into a single group
+///
+/// Days follow the time order when sorting by time and are newest first
+/// otherwise; backups of unknown time come last.
+fn group_backups(backups: &[BackupInfo], sort: BackupSort, by_day: bool) -> Vec<BackupGroup<'_>> {
+    let mut sorted: Vec<&BackupInfo> = backups.iter().collect();
+    sorted.sort_by(|a, b| {
+        let order = match sort.column {
+            BackupSortColumn::Time => a.time().cmp(&b.time()),
+            BackupSortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            BackupSortColumn::Size => a.size.cmp(&b.size),
+        };
+        let order = if sort.ascending {
+            order
+        } else {
+            order.reverse()
+        };
+        order.then_with(|| a.name.cmp(&b.name))
+    });
+    if !by_day {
+        return vec![BackupGroup {
+            day: None,
+            backups: sorted,
+        }];
+    }
+
+    let mut groups: Vec<BackupGroup> = Vec::new();
+    for backup in sorted {
+        let day = backup.time().map(|time| time.date_naive());
+        match groups.iter_mut().find(|group| group.day == day) {
+            Some(group) => group.backups.push(backup),
+            None => groups.push(BackupGroup {
+                day,
+                backups: vec![backup],
+            }),
+        }
+    }
+    let days_ascending = sort.column == BackupSortColumn::Time && sort.ascending;
+    groups.sort_by(|a, b| match (a.day, b.day) {
+        (Some(a), Some(b)) if days_ascending => a.cmp(&b),
+        (Some(a), Some(b)) => b.cmp(&a),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+    groups
+}
+
+/// Second line of a backup row: time, size, and format and number of files once they are read
+fn backup_summary(backup: &BackupInfo, details: Option<ArchiveDetails>) -> String {
+    let mut parts = Vec::new();
+    if let Some(time) = backup.time() {
+        parts.push(time.format("%H:%M:%S").to_string());
+    }
+    parts.push(format_size(backup.size));
+    if let Some(details) = details {
+        parts.push(details.format.label());
+        if let Some(count) = details.entry_count {
+            parts.push(t!("app.backup_files", count = count).to_string());
+        }
+    }
+    parts.join(" · ")
+}
+
+/// Every known time of a backup, for its hover text
+fn backup_times(backup: &BackupInfo) -> String {
+    let format = |time: DateTime<Local>| time.format("%Y-%m-%d %H:%M:%S").to_string();
+    let unknown = || t!("app.unknown_time").to_string();
+    let name_time = backup
+        .name_time
+        .map_or_else(unknown, |time| time.format("%Y-%m-%d %H:%M:%S").to_string());
+    let created = backup
+        .created
+        .map(DateTime::from)
+        .map_or_else(unknown, format);
+    let modified = backup
+        .modified
+        .map(DateTime::from)
+        .map_or_else(unknown, format);
+    [
+        t!("app.backup_name_time", time = name_time),
+        t!("app.backup_created", time = created),
+        t!("app.backup_modified", time = modified),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use std::path::PathBuf;
+
+    fn backup(name: &str, time: Option<&str>, size: u64) -> BackupInfo {
+        BackupInfo {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            size,
+            created: None,
+            modified: None,
+            name_time: time
+                .map(|time| NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap()),
+        }
+    }
+
+    fn names<'a>(groups: &[BackupGroup<'a>]) -> Vec<Vec<&'a str>> {
+        groups
+            .iter()
+            .map(|group| {
+                group
+                    .backups
+                    .iter()
+                    .map(|backup| backup.name.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_backups_by_day() {
+        let backups = vec![
+            backup("a", Some("2026-01-12 08:00"), 30),
+            backup("b", Some("2026-01-13 09:00"), 10),
+            backup("c", None, 50),
+            backup("d", Some("2026-01-12 20:00"), 20),
+        ];
+        let by = |column, ascending| BackupSort { column, ascending };
+
+        let newest_first = group_backups(&backups, BackupSort::default(), true);
+        assert_eq!(
+            names(&newest_first),
+            vec![vec!["b"], vec!["d", "a"], vec!["c"]]
+        );
+        assert_eq!(newest_first[0].day, NaiveDate::from_ymd_opt(2026, 1, 13));
+        assert_eq!(
+            names(&group_backups(
+                &backups,
+                by(BackupSortColumn::Time, true),
+                true
+            )),
+            vec![vec!["a", "d"], vec!["b"], vec!["c"]]
+        );
+        assert_eq!(
+            names(&group_backups(
+                &backups,
+                by(BackupSortColumn::Size, false),
+                true
+            )),
+            vec![vec!["b"], vec!["a", "d"], vec!["c"]]
+        );
+        assert_eq!(
+            names(&group_backups(
+                &backups,
+                by(BackupSortColumn::Size, false),
+                false
+            )),
+            vec![vec!["c", "a", "d", "b"]]
+        );
+    }
+}
@@ -0,0 +1,458 @@
+use eframe::egui;
+use rust_i18n::t;
+use std::path::PathBuf;
+
+use super::{HytaleBackupApp, TAB_CONTENT_MAX_HEIGHT};
+use crate::backup::{inspect_archive, unlock_manifest, verify_existing_archive};
+use crate::batch::BatchJob;
+use crate::history::{
+    add_entry, append_history, clear_history, format_duration, HistoryEntry, Outcome,
+};
+use crate::jobs::{summarize_files, JobReport};
+use crate::models::{
+    BackupProgress, BatchResult, JobKind, PendingVerify, ProgressStage, VerifyOutcome,
+};
+use crate::retention::{apply_retention, safe_to_prune};
+use crate::settings::Settings;
+use crate::utils::{format_size, open_file_in_finder};
+use crate::volumes::archive_size;
+
+impl HytaleBackupApp {
+    /// Shows running jobs with their progress and the queued ones below
+    pub(super) fn render_jobs(&self, ui: &mut egui::Ui) {
+        let statuses = self.jobs.statuses();
+        if statuses.is_empty() {
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("jobs")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for status in &statuses {
+                    let label = format!("{} · {}", job_kind_label(status.kind), status.world);
+                    if status.progress.is_running {
+                        ui.label(egui::RichText::new(label).strong());
+                        render_progress_bar(ui, &status.progress);
+                        ui.add_space(5.0);
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(format!("⏳ {}", label)).weak());
+                            if ui
+                                .small_button("✖")
+                                .on_hover_text(t!("app.cancel_job"))
+                                .clicked()
+                            {
+                                self.jobs.cancel(status.id);
+                            }
+                        });
+                    }
+                }
+            });
+        ui.separator();
+    }
+
+    /// Shows the results of finished jobs and records them in the history
+    pub(super) fn apply_finished_jobs(&mut self, ctx: &egui::Context) {
+        let finished = self.jobs.take_finished();
+        if finished.is_empty() {
+            return;
+        }
+
+        let mut imported = false;
+        for job in &finished {
+            self.status_message = match &job.result {
+                Ok(report) => report.message.clone(),
+                Err(e) => format!("{} {}", t!("app.error"), e),
+            };
+            imported |= job.kind == JobKind::Import && job.result.is_ok();
+        }
+
+        // Also picks up what the daemon recorded since the history was loaded
+        let entries: Vec<HistoryEntry> = finished.iter().map(HistoryEntry::from_job).collect();
+        match append_history(entries.clone()) {
+            Ok(history) => self.history = history,
+            Err(e) => {
+                for entry in entries {
+                    add_entry(&mut self.history, entry);
+                }
+                self.status_message = format!("{} {}", t!("app.error"), e);
+            }
+        }
+        if imported {
+            self.refresh_worlds(ctx);
+        }
+    }
+
+    pub(super) fn render_history_tab(&mut self, ui: &mut egui::Ui) {
+        if self.history.is_empty() {
+            ui.label(t!("app.no_history"));
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(t!("app.history_count", count = self.history.len()));
+            if ui.button(t!("app.clear_history")).clicked() {
+                self.history.clear();
+                if let Err(e) = clear_history() {
+                    self.status_message = format!("{} {}", t!("app.error"), e);
+                }
+            }
+        });
+        ui.add_space(5.0);
+
+        egui::ScrollArea::vertical()
+            .id_salt("history_list")
+            .max_height(TAB_CONTENT_MAX_HEIGHT)
+            .show(ui, |ui| {
+                egui::Grid::new("history")
+                    .num_columns(7)
+                    .striped(true)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        for entry in self.history.iter().rev() {
+                            ui.label(egui::RichText::new(&entry.time).weak());
+                            ui.label(job_kind_label(entry.kind));
+                            ui.label(egui::RichText::new(&entry.world).strong());
+                            ui.label(entry.size.map(format_size).unwrap_or_default());
+                            ui.label(format_duration(entry.duration_secs));
+                            match &entry.outcome {
+                                Outcome::Succeeded => ui.label(
+                                    egui::RichText::new(t!("app.history_succeeded"))
+                                        .color(egui::Color32::from_rgb(100, 200, 100)),
+                                ),
+                                Outcome::Warning(warning) => ui
+                                    .label(
+                                        egui::RichText::new(t!("app.history_warning"))
+                                            .color(egui::Color32::from_rgb(255, 180, 100)),
+                                    )
+                                    .on_hover_text(warning),
+                                Outcome::Failed(e) => ui
+                                    .label(
+                                        egui::RichText::new(t!("app.history_failed"))
+                                            .color(egui::Color32::from_rgb(255, 100, 100)),
+                                    )
+                                    .on_hover_text(e),
+                            };
+                            match &entry.destination {
+                                Some(destination) if destination.exists() => {
+                                    if ui
+                                        .small_button("📂")
+                                        .on_hover_text(destination.to_string_lossy())
+                                        .clicked()
+                                    {
+                                        open_file_in_finder(destination);
+                                    }
+                                }
+                                _ => {
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// Lets the user pick an archive and queues its verification
+    pub(super) fn handle_verify_button(&mut self, ctx: &egui::Context) {
+        let Some(zip_path) = rfd::FileDialog::new()
+            .add_filter("ZIP", &["zip", "001"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let info = match inspect_archive(&zip_path) {
+            Ok(info) => info,
+            Err(e) => {
+                self.status_message = format!("{} {}", t!("app.error"), e);
+                return;
+            }
+        };
+        let Some(world_name) = info
+            .manifest
+            .as_ref()
+            .map(|manifest| manifest.world_name.clone())
+        else {
+            self.status_message = t!("app.error_no_manifest").to_string();
+            return;
+        };
+
+        let pending = PendingVerify {
+            zip_path,
+            world_name,
+            info,
+            password: String::new(),
+        };
+        if pending.info.encrypted {
+            self.pending_verify = Some(pending);
+        } else {
+            self.enqueue_verify(ctx, pending);
+        }
+    }
+
+    /// Asks for the password of an encrypted archive before verifying it
+    pub(super) fn render_verify_dialog(&mut self, ctx: &egui::Context) {
+        let Some(mut pending) = self.pending_verify.take() else {
+            return;
+        };
+        let mut keep_open = true;
+
+        egui::Window::new(t!("app.verify_archive_title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&pending.world_name).strong());
+                ui.add_space(10.0);
+                ui.label(t!("app.import_password_hint"));
+                ui.add(egui::TextEdit::singleline(&mut pending.password).password(true));
+                ui.add_space(15.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button(t!("app.cancel")).clicked() {
+                        keep_open = false;
+                    }
+                    if ui
+                        .add_enabled(
+                            !pending.password.is_empty(),
+                            egui::Button::new(t!("app.verify_archive")),
+                        )
+                        .clicked()
+                    {
+                        keep_open = false;
+                        self.enqueue_verify(ctx, pending.clone());
+                    }
+                });
+            });
+
+        if keep_open {
+            self.pending_verify = Some(pending);
+        }
+    }
+
+    fn enqueue_verify(&mut self, ctx: &egui::Context, pending: PendingVerify) {
+        let world = pending.world_name.clone();
+        self.jobs.push(
+            JobKind::Verify,
+            world,
+            Vec::new(),
+            move |progress, ctx| {
+                let password = (!pending.password.is_empty()).then_some(pending.password.as_str());
+                let manifest = unlock_manifest(&pending.zip_path, &pending.info, password)
+                    .and_then(|manifest| {
+                        manifest.ok_or_else(|| t!("app.error_no_manifest").to_string())
+                    })
+                    .map_err(|e| format!("{} {}", t!("app.verify_failed"), e))?;
+                match verify_existing_archive(&pending.zip_path, &manifest, password, progress, ctx)
+                {
+                    VerifyOutcome::Verified(count) => Ok(JobReport {
+                        size: Some(archive_size(&pending.zip_path)),
+                        destination: pending.zip_path,
+                        message: t!("app.verify_success", count = count).to_string(),
+                        warning: None,
+                    }),
+                    VerifyOutcome::Failed(e) => Err(format!("{} {}", t!("app.verify_failed"), e)),
+                }
+            },
+            ctx,
+        );
+    }
+}
+
+/// Progress of a running job
+fn render_progress_bar(ui: &mut egui::Ui, progress_state: &BackupProgress) {
+    ui.vertical_centered(|ui| {
+        if progress_state.world_count > 0 {
+            ui.label(
+                egui::RichText::new(t!(
+                    "app.batch_progress",
+                    index = progress_state.world_index + 1,
+                    count = progress_state.world_count,
+                    name = progress_state.world_name
+                ))
+                .strong(),
+            );
+            ui.add(egui::ProgressBar::new(
+                progress_state.world_index as f32 / progress_state.world_count as f32,
+            ));
+        }
+
+        ui.label(match progress_state.stage {
+            ProgressStage::Snapshotting => t!("app.snapshotting"),
+            ProgressStage::Compressing => t!("app.compressing"),
+            ProgressStage::Verifying => t!("app.verifying"),
+        });
+
+        let progress_fraction = if progress_state.total > 0 {
+            progress_state.current as f32 / progress_state.total as f32
+        } else {
+            0.0
+        };
+
+        ui.add(
+            egui::ProgressBar::new(progress_fraction)
+                .show_percentage()
+                .animate(true),
+        );
+
+        ui.label(format!(
+            "{} / {}",
+            progress_state.current, progress_state.total
+        ));
+
+        if !progress_state.current_file.is_empty() {
+            ui.label(&progress_state.current_file);
+        }
+    });
+}
+
+fn job_kind_label(kind: JobKind) -> String {
+    match kind {
+        JobKind::Export => t!("app.job_export").to_string(),
+        JobKind::Import => t!("app.job_import").to_string(),
+        JobKind::Verify => t!("app.job_verify").to_string(),
+    }
+}
+
+/// Sums up a batch export; it failed only if no world could be exported
+pub(super) fn batch_job_report(
+    results: &[BatchResult],
+    destination: PathBuf,
+    bundled: bool,
+) -> Result<JobReport, String> {
+    let succeeded = results
+        .iter()
+        .filter(|result| result.result.is_ok())
+        .count();
+    let mut message = t!(
+        "app.batch_summary",
+        succeeded = succeeded,
+        count = results.len()
+    )
+    .to_string();
+    let mut problems = Vec::new();
+    for result in results {
+        match &result.result {
+            Ok(report) => {
+                let mut line = format!("\n✔ {}", result.world_name);
+                let mut world_problems = Vec::new();
+                if let Some(VerifyOutcome::Failed(e)) = &report.verification {
+                    world_problems.push(format!("{} {}", t!("app.verify_failed"), e));
+                }
+                if !report.changed_files.is_empty() {
+                    world_problems.push(
+                        t!(
+                            "app.files_changed_during_export",
+                            count = report.changed_files.len(),
+                            files = summarize_files(&report.changed_files)
+                        )
+                        .to_string(),
+                    );
+                }
+                for problem in world_problems {
+                    line.push_str(&format!(" ({})", problem));
+                    problems.push(format!("{}: {}", result.world_name, problem));
+                }
+                message.push_str(&line);
+            }
+            Err(e) => {
+                message.push_str(&format!("\n✖ {}: {}", result.world_name, e));
+                problems.push(format!("{}: {}", result.world_name, e));
+            }
+        }
+    }
+
+    if succeeded == 0 {
+        return Err(problems.join("\n"));
+    }
+
+    let size = if bundled {
+        archive_size(&destination)
+    } else {
+        results
+            .iter()
+            .filter_map(|result| result.result.as_ref().ok())
+            .map(|report| archive_size(&report.path))
+            .sum()
+    };
+    Ok(JobReport {
+        destination,
+        size: Some(size),
+        message,
+        warning: (!problems.is_empty()).then(|| problems.join("\n")),
+    })
+}
+
+/// Applies the retention rules of each exported world of a batch
+pub(super) fn prune_after_batch(
+    settings: &Settings,
+    jobs: &[BatchJob],
+    results: &[BatchResult],
+    report: &mut JobReport,
+) {
+    let mut deleted = 0;
+    let mut skipped = false;
+    for (job, result) in jobs.iter().zip(results) {
+        let Ok(export) = &result.result else {
+            continue;
+        };
+        if settings.retention_policy(&job.world_path).is_none() {
+            continue;
+        }
+        if !safe_to_prune(export) {
+            skipped = true;
+            continue;
+        }
+        match apply_retention(settings, &job.world_path, &export.path) {
+            Ok(count) => deleted += count,
+            Err(e) => {
+                let warning = format!("{}: {} {}", job.world_name, t!("app.prune_failed"), e);
+                report.message.push_str(&format!("\n{}", warning));
+                report.add_warning(warning);
+            }
+        }
+    }
+    if deleted > 0 {
+        report
+            .message
+            .push_str(&format!("\n{}", t!("app.archives_pruned", count = deleted)));
+    }
+    if skipped {
+        report
+            .message
+            .push_str(&format!("\n{}", t!("app.prune_skipped")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExportReport;
+
+    #[test]
+    fn test_batch_job_report_lists_changed_files() {
+        let export = |name: &str, changed_files: Vec<String>| BatchResult {
+            world_name: name.to_string(),
+            result: Ok(ExportReport {
+                path: PathBuf::from(format!("{}.zip", name)),
+                volume_count: 1,
+                signed_by: None,
+                verification: None,
+                changed_files,
+            }),
+        };
+        let results = [
+            export("Calm", Vec::new()),
+            export("Busy", vec!["chunks/0.region".to_string()]),
+        ];
+
+        let report = batch_job_report(&results, PathBuf::from("out"), false).unwrap();
+        let warning = report.warning.unwrap();
+        assert!(warning.starts_with("Busy: "));
+        assert!(warning.contains("chunks/0.region"));
+        assert!(!warning.contains("Calm"));
+        assert!(report.message.contains("chunks/0.region"));
+    }
+}
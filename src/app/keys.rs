@@ -0,0 +1,206 @@
+use eframe::egui;
+use rust_i18n::t;
+
+use super::HytaleBackupApp;
+use crate::signing::{
+    fingerprint, generate_identity, load_trusted_keys, parse_public_key, save_trusted_keys,
+    SignatureStatus, TrustedKey,
+};
+
+/// State of the signing keys window
+#[derive(Default)]
+pub struct KeyManager {
+    pub trusted_keys: Vec<TrustedKey>,
+    pub new_identity_name: String,
+    pub new_trusted_name: String,
+    pub new_trusted_key: String,
+}
+
+impl HytaleBackupApp {
+    pub(super) fn open_key_manager(&mut self) {
+        self.key_manager = Some(KeyManager {
+            trusted_keys: load_trusted_keys(),
+            ..Default::default()
+        });
+    }
+
+    pub(super) fn render_key_manager(&mut self, ctx: &egui::Context) {
+        let Some(mut manager) = self.key_manager.take() else {
+            return;
+        };
+        let mut open = true;
+
+        egui::Window::new(t!("app.signing_keys_title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(t!("app.own_key")).strong());
+                ui.add_space(5.0);
+
+                match &self.signing_identity {
+                    Some(identity) => {
+                        let public_key = identity.public_key_hex();
+                        ui.horizontal(|ui| {
+                            ui.label(&identity.name);
+                            ui.label(
+                                egui::RichText::new(fingerprint(&public_key))
+                                    .monospace()
+                                    .weak(),
+                            );
+                            if ui
+                                .button("📋")
+                                .on_hover_text(t!("app.copy_public_key"))
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(public_key.clone());
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label(t!("app.no_own_key"));
+                        ui.horizontal(|ui| {
+                            ui.label(t!("app.key_name"));
+                            ui.text_edit_singleline(&mut manager.new_identity_name);
+                            let name = manager.new_identity_name.trim().to_string();
+                            if ui
+                                .add_enabled(
+                                    !name.is_empty(),
+                                    egui::Button::new(t!("app.generate_key")),
+                                )
+                                .clicked()
+                            {
+                                match generate_identity(&name) {
+                                    Ok(identity) => {
+                                        self.signing_identity = Some(identity);
+                                        manager.trusted_keys = load_trusted_keys();
+                                        self.status_message = t!("app.key_generated").to_string();
+                                    }
+                                    Err(e) => {
+                                        self.status_message = format!("{} {}", t!("app.error"), e);
+                                    }
+                                }
+                            }
+                        });
+                    }
+                }
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.label(egui::RichText::new(t!("app.trusted_keys")).strong());
+                ui.add_space(5.0);
+
+                let mut remove = None;
+                egui::ScrollArea::vertical()
+                    .id_salt("trusted_keys")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        if manager.trusted_keys.is_empty() {
+                            ui.label(egui::RichText::new(t!("app.no_trusted_keys")).weak());
+                        }
+                        for (index, key) in manager.trusted_keys.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(&key.name);
+                                ui.label(
+                                    egui::RichText::new(fingerprint(&key.public_key))
+                                        .monospace()
+                                        .weak(),
+                                );
+                                if ui.button("🗑").on_hover_text(t!("app.remove_key")).clicked() {
+                                    remove = Some(index);
+                                }
+                            });
+                        }
+                    });
+
+                if let Some(index) = remove {
+                    manager.trusted_keys.remove(index);
+                    self.store_trusted_keys(&manager.trusted_keys);
+                }
+
+                ui.add_space(5.0);
+                egui::Grid::new("add_trusted_key")
+                    .num_columns(2)
+                    .spacing([10.0, 5.0])
+                    .show(ui, |ui| {
+                        ui.label(t!("app.key_name"));
+                        ui.text_edit_singleline(&mut manager.new_trusted_name);
+                        ui.end_row();
+
+                        ui.label(t!("app.public_key"));
+                        ui.text_edit_singleline(&mut manager.new_trusted_key);
+                        ui.end_row();
+                    });
+
+                let valid = !manager.new_trusted_name.trim().is_empty()
+                    && parse_public_key(&manager.new_trusted_key).is_some();
+                if ui
+                    .add_enabled(valid, egui::Button::new(t!("app.add_trusted_key")))
+                    .clicked()
+                {
+                    manager.trusted_keys.push(TrustedKey {
+                        name: manager.new_trusted_name.trim().to_string(),
+                        public_key: manager.new_trusted_key.trim().to_lowercase(),
+                    });
+                    manager.new_trusted_name.clear();
+                    manager.new_trusted_key.clear();
+                    self.store_trusted_keys(&manager.trusted_keys);
+                }
+            });
+
+        if open {
+            self.key_manager = Some(manager);
+        }
+    }
+
+    fn store_trusted_keys(&mut self, keys: &[TrustedKey]) {
+        if let Err(e) = save_trusted_keys(keys) {
+            self.status_message = format!("{} {}", t!("app.error"), e);
+        }
+    }
+
+    /// Shows who signed an archive in the import dialog
+    pub(super) fn render_signature_status(&mut self, ui: &mut egui::Ui, status: &SignatureStatus) {
+        match status {
+            SignatureStatus::Trusted { signer } => {
+                ui.label(
+                    egui::RichText::new(t!("app.signed_by", name = signer))
+                        .color(egui::Color32::from_rgb(100, 200, 100)),
+                );
+            }
+            SignatureStatus::Untrusted { signer, public_key } => {
+                ui.label(
+                    egui::RichText::new(t!(
+                        "app.signed_by_untrusted",
+                        name = signer,
+                        fingerprint = fingerprint(public_key)
+                    ))
+                    .color(egui::Color32::from_rgb(255, 180, 100)),
+                );
+                if ui.button(t!("app.trust_key")).clicked() {
+                    let mut keys = load_trusted_keys();
+                    keys.push(TrustedKey {
+                        name: signer.clone(),
+                        public_key: public_key.clone(),
+                    });
+                    self.store_trusted_keys(&keys);
+                    if let Some(pending) = self.pending_import.as_mut() {
+                        pending.info.signature = SignatureStatus::Trusted {
+                            signer: signer.clone(),
+                        };
+                    }
+                }
+            }
+            SignatureStatus::Unsigned => {
+                ui.label(egui::RichText::new(t!("app.unsigned")).weak());
+            }
+            SignatureStatus::Tampered => {
+                ui.label(
+                    egui::RichText::new(t!("app.tampered"))
+                        .color(egui::Color32::from_rgb(255, 100, 100)),
+                );
+            }
+        }
+    }
+}
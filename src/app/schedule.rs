@@ -0,0 +1,291 @@
+use chrono::Local;
+use eframe::egui;
+use rust_i18n::t;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::HytaleBackupApp;
+use crate::models::{BackupSchedule, JobKind, RetentionPolicy, ScheduleKind, WorldRetention};
+use crate::scheduler::{parse_cron, scheduled_export, ScheduleLock, Scheduler};
+use crate::session::SessionMonitor;
+use crate::settings::save_settings;
+
+/// Interval a new interval schedule starts with
+const DEFAULT_INTERVAL_MINUTES: u64 = 360;
+
+/// Cron expression a new cron schedule starts with, every day at 3:00
+const DEFAULT_CRON: &str = "0 3 * * *";
+
+/// Rules a world starts with when deleting old archives is turned on
+const DEFAULT_RETENTION: RetentionPolicy = RetentionPolicy {
+    keep_last: 3,
+    keep_daily: 7,
+    keep_weekly: 4,
+    keep_monthly: 6,
+    max_total_mb: 0,
+};
+
+/// Longest time between two checks of the schedules while the app is idle
+const SCHEDULE_CHECK_SECS: u64 = 60;
+
+impl HytaleBackupApp {
+    /// Whether this window runs the automatic backups
+    ///
+    /// While the daemon or another window holds the schedule lock, taking it
+    /// over is tried again at most every [`SCHEDULE_CHECK_SECS`].
+    fn runs_automatic_backups(&mut self, ctx: &egui::Context) -> bool {
+        if self.schedule_lock.is_some() {
+            return true;
+        }
+        let interval = Duration::from_secs(SCHEDULE_CHECK_SECS);
+        if self
+            .schedule_lock_checked
+            .is_some_and(|checked| checked.elapsed() < interval)
+        {
+            return false;
+        }
+        self.schedule_lock_checked = Some(Instant::now());
+        self.schedule_lock = ScheduleLock::try_acquire();
+        // Whoever held the lock may have made backups in the meantime
+        self.scheduler = Scheduler::load();
+        ctx.request_repaint_after(interval);
+        self.schedule_lock.is_some()
+    }
+
+    /// Queues exports of worlds whose scheduled backup is due
+    pub(super) fn run_schedules(&mut self, ctx: &egui::Context) {
+        if !self.runs_automatic_backups(ctx) {
+            return;
+        }
+        let now = Local::now();
+        for path in self.scheduler.take_due(&self.settings.schedules, now) {
+            self.queue_automatic_backup(&path, ctx);
+        }
+        if let Err(e) = self.scheduler.save() {
+            self.status_message = format!("{} {}", t!("app.error"), e);
+        }
+
+        if let Some(next) = self.scheduler.next_due(&self.settings.schedules) {
+            let wait = (next - now)
+                .to_std()
+                .unwrap_or_default()
+                .min(Duration::from_secs(SCHEDULE_CHECK_SECS));
+            ctx.request_repaint_after(wait);
+        }
+    }
+
+    /// Queues exports of worlds whose play session ended
+    pub(super) fn run_session_backups(&mut self, ctx: &egui::Context) {
+        if !self.settings.backup_on_game_exit || self.schedule_lock.is_none() {
+            self.session_monitor = None;
+            return;
+        }
+        let monitor = self.session_monitor.get_or_insert_with(|| {
+            let monitor = SessionMonitor::new(ctx);
+            monitor.set_worlds(self.worlds.iter().map(|world| world.path.clone()).collect());
+            monitor
+        });
+        for path in monitor.take_ended() {
+            self.queue_automatic_backup(&path, ctx);
+        }
+    }
+
+    /// Queues an unattended export of a world into the folder for scheduled backups
+    fn queue_automatic_backup(&mut self, path: &Path, ctx: &egui::Context) {
+        let Some(world) = self.worlds.iter().find(|world| world.path == path).cloned() else {
+            self.status_message = format!(
+                "{} {}",
+                t!("app.error"),
+                t!(
+                    "errors.scheduled_world_missing",
+                    path = path.to_string_lossy()
+                )
+            );
+            return;
+        };
+        let Some(directory) = self.settings.schedule_directory().map(Path::to_path_buf) else {
+            self.status_message = format!("{} {}", t!("app.error"), t!("errors.no_schedule_dir"));
+            return;
+        };
+
+        let settings = self.settings.clone();
+        self.jobs.push(
+            JobKind::Export,
+            world.name.clone(),
+            vec![world.path.clone()],
+            move |progress, ctx| scheduled_export(&world, &settings, &directory, progress, ctx),
+            ctx,
+        );
+    }
+
+    /// Schedule editor of the selected world
+    pub(super) fn render_schedule(&mut self, ui: &mut egui::Ui, world_path: &Path) {
+        let position = self
+            .settings
+            .schedules
+            .iter()
+            .position(|schedule| schedule.world == world_path);
+        let mut kind = position.map(|index| self.settings.schedules[index].kind.clone());
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label(t!("app.automatic_backup"));
+            let before = kind.clone();
+            egui::ComboBox::from_id_salt("schedule_kind")
+                .selected_text(schedule_label(kind.as_ref()))
+                .show_ui(ui, |ui| {
+                    let options = [
+                        None,
+                        Some(ScheduleKind::Interval(DEFAULT_INTERVAL_MINUTES)),
+                        Some(ScheduleKind::Cron(DEFAULT_CRON.to_string())),
+                    ];
+                    for option in options {
+                        let selected = option.as_ref().map(std::mem::discriminant)
+                            == kind.as_ref().map(std::mem::discriminant);
+                        if ui
+                            .selectable_label(selected, schedule_label(option.as_ref()))
+                            .clicked()
+                            && !selected
+                        {
+                            kind = option;
+                        }
+                    }
+                });
+            changed |= kind != before;
+
+            match kind.as_mut() {
+                Some(ScheduleKind::Interval(minutes)) => {
+                    ui.label(t!("app.schedule_every"));
+                    let interval = ui.add(
+                        egui::DragValue::new(minutes)
+                            .range(5..=10_080)
+                            .suffix(" min"),
+                    );
+                    changed |=
+                        interval.drag_stopped() || (interval.changed() && !interval.dragged());
+                }
+                Some(ScheduleKind::Cron(expression)) => {
+                    let edit = ui
+                        .add(egui::TextEdit::singleline(expression).desired_width(140.0))
+                        .on_hover_text(t!("app.schedule_cron_hint"));
+                    changed |= edit.lost_focus();
+                    if let Err(e) = parse_cron(expression) {
+                        ui.label(
+                            egui::RichText::new(e)
+                                .small()
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        );
+                    }
+                }
+                None => {}
+            }
+
+            if let Some(schedule) = position.map(|index| &self.settings.schedules[index]) {
+                if let Some(next) = self.scheduler.next_run(schedule) {
+                    ui.label(
+                        egui::RichText::new(t!(
+                            "app.next_backup",
+                            time = next.format("%Y-%m-%d %H:%M").to_string()
+                        ))
+                        .weak(),
+                    );
+                }
+                if self.schedule_lock.is_none() {
+                    ui.label(egui::RichText::new("ℹ").weak())
+                        .on_hover_text(t!("app.schedules_run_elsewhere"));
+                }
+            }
+        });
+
+        // Text edits write to the schedule on every key press, the file is written once
+        if let (Some(index), Some(kind)) = (position, kind.clone()) {
+            self.settings.schedules[index].kind = kind;
+        }
+        if !changed {
+            return;
+        }
+        match (position, kind) {
+            (Some(index), None) => {
+                self.settings.schedules.remove(index);
+            }
+            (None, Some(kind)) => self.settings.schedules.push(BackupSchedule {
+                world: world_path.to_path_buf(),
+                kind,
+            }),
+            _ => {}
+        }
+        if let Err(e) = save_settings(&self.settings) {
+            self.status_message = format!("{} {}", t!("app.error"), e);
+        }
+    }
+
+    /// Retention rules editor of the selected world
+    pub(super) fn render_retention(&mut self, ui: &mut egui::Ui, world_path: &Path) {
+        let position = self
+            .settings
+            .retention
+            .iter()
+            .position(|retention| retention.world == world_path);
+        let mut enabled = position.is_some();
+        let mut policy = position
+            .map(|index| self.settings.retention[index].policy.clone())
+            .unwrap_or(DEFAULT_RETENTION);
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut enabled, t!("app.delete_old_archives"))
+                .on_hover_text(t!("app.delete_old_archives_hint"))
+                .changed();
+            if !enabled {
+                return;
+            }
+            let fields = [
+                (t!("app.keep_last"), &mut policy.keep_last),
+                (t!("app.keep_daily"), &mut policy.keep_daily),
+                (t!("app.keep_weekly"), &mut policy.keep_weekly),
+                (t!("app.keep_monthly"), &mut policy.keep_monthly),
+            ];
+            for (label, value) in fields {
+                ui.label(label);
+                let drag = ui.add(egui::DragValue::new(value).range(0..=999));
+                changed |= drag.drag_stopped() || (drag.changed() && !drag.dragged());
+            }
+            ui.label(t!("app.max_total_size"));
+            let drag = ui.add(
+                egui::DragValue::new(&mut policy.max_total_mb)
+                    .range(0..=1_000_000)
+                    .suffix(" MB"),
+            );
+            changed |= drag.drag_stopped() || (drag.changed() && !drag.dragged());
+        });
+
+        if let Some(index) = position {
+            self.settings.retention[index].policy = policy.clone();
+        }
+        if !changed {
+            return;
+        }
+        match (position, enabled) {
+            (Some(index), false) => {
+                self.settings.retention.remove(index);
+            }
+            (None, true) => self.settings.retention.push(WorldRetention {
+                world: world_path.to_path_buf(),
+                policy,
+            }),
+            _ => {}
+        }
+        if let Err(e) = save_settings(&self.settings) {
+            self.status_message = format!("{} {}", t!("app.error"), e);
+        }
+    }
+}
+
+fn schedule_label(kind: Option<&ScheduleKind>) -> String {
+    match kind {
+        None => t!("app.schedule_off").to_string(),
+        Some(ScheduleKind::Interval(_)) => t!("app.schedule_interval").to_string(),
+        Some(ScheduleKind::Cron(_)) => t!("app.schedule_cron").to_string(),
+    }
+}
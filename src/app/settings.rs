@@ -0,0 +1,407 @@
+use eframe::egui;
+use rust_i18n::t;
+
+use super::HytaleBackupApp;
+use crate::discovery::discover_save_roots;
+use crate::models::Compression;
+use crate::server::find_universes;
+use crate::settings::{save_settings, SaveRoot, ServerProfile, DEFAULT_FILENAME_TEMPLATE};
+use crate::template::{render, TemplateContext};
+
+/// State of the settings window
+#[derive(Default)]
+pub struct SettingsWindow {
+    /// Save roots being edited, stored when a change is committed
+    pub save_roots: Vec<SaveRoot>,
+    /// Save folders of Hytale installations found on this system
+    pub detected_roots: Vec<SaveRoot>,
+    pub server_profiles: Vec<ServerProfile>,
+    /// Comma-separated export tags being edited
+    pub tags_text: String,
+}
+
+impl HytaleBackupApp {
+    pub(super) fn open_settings_window(&mut self) {
+        self.settings_window = Some(SettingsWindow {
+            save_roots: self.settings.save_roots.clone(),
+            detected_roots: discover_save_roots(),
+            server_profiles: self.settings.server_profiles.clone(),
+            tags_text: self.settings.export_tags.join(", "),
+        });
+    }
+
+    pub(super) fn render_settings_window(&mut self, ctx: &egui::Context) {
+        let Some(mut window) = self.settings_window.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut changed = false;
+
+        egui::Window::new(t!("app.settings_title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if self.render_export_settings(ui, &mut window.tags_text) {
+                    if let Err(e) = save_settings(&self.settings) {
+                        self.status_message = format!("{} {}", t!("app.error"), e);
+                    }
+                }
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.label(egui::RichText::new(t!("app.save_roots")).strong());
+                ui.add_space(5.0);
+
+                let mut remove = None;
+                egui::ScrollArea::vertical()
+                    .id_salt("save_roots")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        if window.save_roots.is_empty() {
+                            ui.label(egui::RichText::new(t!("app.no_save_roots")).weak());
+                        }
+                        for (index, root) in window.save_roots.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                let name = ui.add(
+                                    egui::TextEdit::singleline(&mut root.name).desired_width(120.0),
+                                );
+                                if name.lost_focus() && !root.name.trim().is_empty() {
+                                    changed = true;
+                                }
+                                ui.label(
+                                    egui::RichText::new(root.path.to_string_lossy())
+                                        .small()
+                                        .weak(),
+                                );
+                                if ui
+                                    .button("🗑")
+                                    .on_hover_text(t!("app.remove_save_root"))
+                                    .clicked()
+                                {
+                                    remove = Some(index);
+                                }
+                            });
+                        }
+                    });
+
+                if let Some(index) = remove {
+                    window.save_roots.remove(index);
+                    changed = true;
+                }
+
+                ui.add_space(5.0);
+                if ui.button(t!("app.add_save_root")).clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| path.to_string_lossy().to_string());
+                        if !window.save_roots.iter().any(|root| root.path == path) {
+                            window.save_roots.push(SaveRoot { name, path });
+                            changed = true;
+                        }
+                    }
+                }
+
+                let suggestions: Vec<SaveRoot> = window
+                    .detected_roots
+                    .iter()
+                    .filter(|detected| {
+                        !window
+                            .save_roots
+                            .iter()
+                            .any(|root| root.path == detected.path)
+                    })
+                    .cloned()
+                    .collect();
+                if !suggestions.is_empty() {
+                    ui.add_space(10.0);
+                    ui.separator();
+                    ui.label(egui::RichText::new(t!("app.detected_save_roots")).strong());
+                    ui.add_space(5.0);
+                    for detected in suggestions {
+                        ui.horizontal(|ui| {
+                            ui.label(&detected.name);
+                            ui.label(
+                                egui::RichText::new(detected.path.to_string_lossy())
+                                    .small()
+                                    .weak(),
+                            );
+                            if ui.button(t!("app.add_detected_root")).clicked() {
+                                window.save_roots.push(detected.clone());
+                                changed = true;
+                            }
+                        });
+                    }
+                }
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.label(egui::RichText::new(t!("app.server_profiles")).strong());
+                ui.add_space(5.0);
+
+                let mut remove = None;
+                if window.server_profiles.is_empty() {
+                    ui.label(egui::RichText::new(t!("app.no_server_profiles")).weak());
+                }
+                for (index, server) in window.server_profiles.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let name = ui
+                            .add(egui::TextEdit::singleline(&mut server.name).desired_width(120.0));
+                        if name.lost_focus() && !server.name.trim().is_empty() {
+                            changed = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(server.path.to_string_lossy())
+                                .small()
+                                .weak(),
+                        );
+                        if ui
+                            .button("🗑")
+                            .on_hover_text(t!("app.remove_server_profile"))
+                            .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                    });
+                }
+
+                if let Some(index) = remove {
+                    window.server_profiles.remove(index);
+                    changed = true;
+                }
+
+                ui.add_space(5.0);
+                if ui.button(t!("app.add_server_profile")).clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                        if find_universes(&path).is_empty() {
+                            self.status_message = t!("app.error_no_universe").to_string();
+                        } else if !window
+                            .server_profiles
+                            .iter()
+                            .any(|server| server.path == path)
+                        {
+                            let name = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_else(|| path.to_string_lossy().to_string());
+                            window.server_profiles.push(ServerProfile { name, path });
+                            changed = true;
+                        }
+                    }
+                }
+
+                if !self.external_roots.is_empty() {
+                    ui.add_space(10.0);
+                    ui.separator();
+                    ui.label(egui::RichText::new(t!("app.external_save_roots")).strong());
+                    ui.add_space(5.0);
+                    for root in &self.external_roots {
+                        ui.horizontal(|ui| {
+                            ui.label(&root.name);
+                            ui.label(
+                                egui::RichText::new(root.path.to_string_lossy())
+                                    .small()
+                                    .weak(),
+                            );
+                        });
+                    }
+                }
+            });
+
+        if changed {
+            self.settings.save_roots = window.save_roots.clone();
+            self.settings.server_profiles = window.server_profiles.clone();
+            if let Err(e) = save_settings(&self.settings) {
+                self.status_message = format!("{} {}", t!("app.error"), e);
+            }
+            self.refresh_worlds(ctx);
+        }
+
+        if open {
+            self.settings_window = Some(window);
+        }
+    }
+
+    /// Shows the export defaults, returns whether one of them changed
+    fn render_export_settings(&mut self, ui: &mut egui::Ui, tags_text: &mut String) -> bool {
+        let mut changed = false;
+        let settings = &mut self.settings;
+
+        ui.label(egui::RichText::new(t!("app.export_settings")).strong());
+        ui.add_space(5.0);
+
+        egui::Grid::new("export_settings")
+            .num_columns(2)
+            .spacing([10.0, 5.0])
+            .show(ui, |ui| {
+                ui.label(t!("app.default_export_dir"));
+                ui.horizontal(|ui| {
+                    let folder = settings
+                        .default_export_dir
+                        .as_ref()
+                        .map(|dir| dir.to_string_lossy().to_string())
+                        .unwrap_or_else(|| t!("app.last_used_folder").to_string());
+                    ui.label(egui::RichText::new(folder).small().weak());
+                    if ui.button(t!("app.browse")).clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            settings.default_export_dir = Some(dir);
+                            changed = true;
+                        }
+                    }
+                    if settings.default_export_dir.is_some()
+                        && ui
+                            .button("✖")
+                            .on_hover_text(t!("app.use_last_folder"))
+                            .clicked()
+                    {
+                        settings.default_export_dir = None;
+                        changed = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.label(t!("app.schedule_dir"));
+                ui.horizontal(|ui| {
+                    let folder = settings
+                        .schedule_dir
+                        .as_ref()
+                        .map(|dir| dir.to_string_lossy().to_string())
+                        .unwrap_or_else(|| t!("app.default_export_folder").to_string());
+                    ui.label(egui::RichText::new(folder).small().weak());
+                    if ui.button(t!("app.browse")).clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            settings.schedule_dir = Some(dir);
+                            changed = true;
+                        }
+                    }
+                    if settings.schedule_dir.is_some()
+                        && ui
+                            .button("✖")
+                            .on_hover_text(t!("app.use_default_export_folder"))
+                            .clicked()
+                    {
+                        settings.schedule_dir = None;
+                        changed = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                changed |= ui
+                    .checkbox(
+                        &mut settings.backup_on_game_exit,
+                        t!("app.backup_on_game_exit"),
+                    )
+                    .on_hover_text(t!("app.backup_on_game_exit_hint"))
+                    .changed();
+                ui.end_row();
+
+                ui.label(t!("app.filename_template"));
+                let template = ui
+                    .text_edit_singleline(&mut settings.filename_template)
+                    .on_hover_text(t!("app.filename_template_hint"));
+                if template.lost_focus() {
+                    if settings.filename_template.trim().is_empty() {
+                        settings.filename_template = DEFAULT_FILENAME_TEMPLATE.to_string();
+                    }
+                    changed = true;
+                }
+                ui.end_row();
+
+                ui.label("");
+                let sample = TemplateContext {
+                    world: "World",
+                    time: chrono::Local::now(),
+                    hostname: "hostname",
+                    size: Some(0),
+                    tags: &settings.export_tags,
+                };
+                match render(&settings.filename_template, &sample, 1) {
+                    Ok(name) => {
+                        ui.label(egui::RichText::new(format!("{}.zip", name)).small().weak());
+                    }
+                    Err(e) => {
+                        ui.label(
+                            egui::RichText::new(e)
+                                .small()
+                                .color(egui::Color32::from_rgb(255, 100, 100)),
+                        );
+                    }
+                }
+                ui.end_row();
+
+                ui.label(t!("app.export_tags"));
+                let tags = ui
+                    .text_edit_singleline(tags_text)
+                    .on_hover_text(t!("app.export_tags_hint"));
+                if tags.lost_focus() {
+                    settings.export_tags = tags_text
+                        .split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect();
+                    changed = true;
+                }
+                ui.end_row();
+
+                ui.label(t!("app.compression"));
+                ui.horizontal(|ui| {
+                    let before = settings.compression;
+                    egui::ComboBox::from_id_salt("compression")
+                        .selected_text(compression_label(settings.compression))
+                        .show_ui(ui, |ui| {
+                            for compression in Compression::ALL {
+                                ui.selectable_value(
+                                    &mut settings.compression,
+                                    compression,
+                                    compression_label(compression),
+                                );
+                            }
+                        });
+                    if settings.compression != before {
+                        // Levels differ between methods
+                        settings.compression_level = None;
+                        changed = true;
+                    }
+
+                    if let Some(levels) = settings.compression.levels() {
+                        let mut custom = settings.compression_level.is_some();
+                        if ui
+                            .checkbox(&mut custom, t!("app.compression_level"))
+                            .changed()
+                        {
+                            settings.compression_level = custom.then(|| *levels.end() / 2);
+                            changed = true;
+                        }
+                        if let Some(level) = settings.compression_level.as_mut() {
+                            let slider = ui.add(egui::Slider::new(level, levels));
+                            // Store once a drag ends instead of on every frame of it
+                            changed |=
+                                slider.drag_stopped() || (slider.changed() && !slider.dragged());
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label(t!("app.parallel_jobs"));
+                let jobs = ui
+                    .add(egui::DragValue::new(&mut settings.max_parallel_jobs).range(1..=4))
+                    .on_hover_text(t!("app.parallel_jobs_hint"));
+                changed |= jobs.drag_stopped() || (jobs.changed() && !jobs.dragged());
+                ui.end_row();
+            });
+
+        changed
+    }
+}
+
+fn compression_label(compression: Compression) -> String {
+    match compression {
+        Compression::Deflated => t!("app.compression_deflated").to_string(),
+        Compression::Zstd => t!("app.compression_zstd").to_string(),
+        Compression::Stored => t!("app.compression_stored").to_string(),
+    }
+}
@@ -0,0 +1,330 @@
+use eframe::egui;
+use egui_extras::{Column, TableBuilder, TableRow};
+use rust_i18n::t;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+use super::HytaleBackupApp;
+use crate::models::{SortColumn, WorldInfo, WorldSort};
+use crate::utils::format_size;
+
+impl HytaleBackupApp {
+    pub(super) fn render_world_list(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("app.available_worlds"));
+            if ui.button(t!("app.refresh")).clicked() {
+                self.refresh_worlds(ui.ctx());
+            }
+            if ui.button(t!("app.import_world")).clicked() {
+                self.handle_import_button();
+            }
+            if ui.button(t!("app.verify_archive")).clicked() {
+                self.handle_verify_button(ui.ctx());
+            }
+            if ui.button(t!("app.signing_keys")).clicked() {
+                self.open_key_manager();
+            }
+            if ui.button(t!("app.settings")).clicked() {
+                self.open_settings_window();
+            }
+            if self.scanner.is_scanning() {
+                ui.spinner().on_hover_text(t!("app.scanning_worlds"));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.world_search)
+                        .hint_text(t!("app.search_worlds"))
+                        .desired_width(160.0),
+                );
+            });
+        });
+
+        ui.add_space(10.0);
+
+        if self.worlds.is_empty() {
+            ui.label(t!("app.no_worlds_found"));
+            return;
+        }
+
+        let visible = visible_worlds(
+            &self.worlds,
+            &self.world_search,
+            self.settings.world_sort,
+            &self.settings.favorite_worlds,
+        );
+        let show_source = self.import_targets().len() > 1;
+        let mut sort = self.settings.world_sort;
+        let mut clicked = None;
+        let mut toggle_favorite = None;
+        // Ctrl-click (Cmd on macOS) adds worlds to the selection for a batch export
+        let add_to_selection = ui.input(|input| input.modifiers.command);
+
+        let mut table = TableBuilder::new(ui)
+            .id_salt("worlds_table")
+            .striped(true)
+            .sense(egui::Sense::click())
+            .max_scroll_height(120.0)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(24.0))
+            .column(Column::remainder().at_least(160.0).clip(true));
+        if show_source {
+            table = table.column(Column::auto().at_least(80.0).clip(true));
+        }
+        table
+            .column(Column::exact(80.0))
+            .column(Column::exact(130.0))
+            .column(Column::exact(70.0))
+            .header(20.0, |mut header| {
+                header.col(|_| {});
+                sort_header(
+                    &mut header,
+                    &mut sort,
+                    SortColumn::Name,
+                    t!("app.column_name"),
+                );
+                if show_source {
+                    header.col(|ui| {
+                        ui.strong(t!("app.column_source"));
+                    });
+                }
+                sort_header(
+                    &mut header,
+                    &mut sort,
+                    SortColumn::Size,
+                    t!("app.column_size"),
+                );
+                sort_header(
+                    &mut header,
+                    &mut sort,
+                    SortColumn::LastPlayed,
+                    t!("app.column_last_played"),
+                );
+                sort_header(
+                    &mut header,
+                    &mut sort,
+                    SortColumn::Backups,
+                    t!("app.column_backups"),
+                );
+            })
+            .body(|body| {
+                body.rows(20.0, visible.len(), |mut row| {
+                    let index = visible[row.index()];
+                    let world = &self.worlds[index];
+                    let favorite = self.settings.favorite_worlds.contains(&world.path);
+                    row.set_selected(
+                        self.selected_world == Some(index)
+                            || self.selected_worlds.contains(&world.path),
+                    );
+
+                    row.col(|ui| {
+                        if ui
+                            .selectable_label(favorite, if favorite { "★" } else { "☆" })
+                            .on_hover_text(t!("app.toggle_favorite"))
+                            .clicked()
+                        {
+                            toggle_favorite = Some(world.path.clone());
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("🌍 {}", world.name));
+                    });
+                    if show_source {
+                        row.col(|ui| {
+                            ui.label(egui::RichText::new(&world.source).weak());
+                        });
+                    }
+                    let details = world.details.as_ref();
+                    row.col(|ui| {
+                        ui.label(details.map_or("…".to_string(), |d| format_size(d.size)));
+                    });
+                    row.col(|ui| {
+                        ui.label(details.map_or("…".to_string(), |d| {
+                            d.last_played.clone().unwrap_or_else(|| "-".to_string())
+                        }));
+                    });
+                    row.col(|ui| {
+                        ui.label(details.map_or("…".to_string(), |d| d.backup_count.to_string()));
+                    });
+
+                    if row.response().clicked() {
+                        clicked = Some(index);
+                    }
+                });
+            });
+
+        self.settings.world_sort = sort;
+        if let Some(index) = clicked {
+            let path = self.worlds[index].path.clone();
+            if !add_to_selection {
+                self.selected_worlds.clear();
+                self.selected_world = Some(index);
+            } else if self.selected_world == Some(index) {
+                // Deselecting the world shown in the details moves them to another selected one
+                self.selected_world = self
+                    .worlds
+                    .iter()
+                    .position(|world| self.selected_worlds.contains(&world.path));
+                if let Some(focused) = self.selected_world {
+                    self.selected_worlds.remove(&self.worlds[focused].path);
+                }
+            } else if !self.selected_worlds.remove(&path) {
+                if let Some(focused) = self.selected_world {
+                    self.selected_worlds
+                        .insert(self.worlds[focused].path.clone());
+                }
+                self.selected_world = Some(index);
+            }
+        }
+        if let Some(path) = toggle_favorite {
+            let favorites = &mut self.settings.favorite_worlds;
+            if let Some(position) = favorites.iter().position(|favorite| *favorite == path) {
+                favorites.remove(position);
+            } else {
+                favorites.push(path);
+            }
+        }
+    }
+}
+
+/// Adds a header cell that sorts by its column, reversing the order when clicked again
+fn sort_header(
+    header: &mut TableRow,
+    sort: &mut WorldSort,
+    column: SortColumn,
+    label: impl Into<String>,
+) {
+    header.col(|ui| {
+        let active = sort.column == column;
+        let arrow = match (active, sort.ascending) {
+            (false, _) => "",
+            (true, true) => " ⬆",
+            (true, false) => " ⬇",
+        };
+        if ui
+            .selectable_label(active, format!("{}{}", label.into(), arrow))
+            .clicked()
+        {
+            if active {
+                sort.ascending = !sort.ascending;
+            } else {
+                *sort = WorldSort {
+                    column,
+                    ascending: true,
+                };
+            }
+        }
+    });
+}
+
+/// Indices of the worlds matching the search, favourites first, each part in the chosen order
+fn visible_worlds(
+    worlds: &[WorldInfo],
+    search: &str,
+    sort: WorldSort,
+    favorites: &[PathBuf],
+) -> Vec<usize> {
+    let search = search.trim().to_lowercase();
+    let mut indices: Vec<usize> = (0..worlds.len())
+        .filter(|&index| {
+            let world = &worlds[index];
+            search.is_empty()
+                || world.name.to_lowercase().contains(&search)
+                || world.source.to_lowercase().contains(&search)
+        })
+        .collect();
+
+    let is_favorite = |world: &WorldInfo| favorites.contains(&world.path);
+    indices.sort_by(|&a, &b| {
+        let (a, b) = (&worlds[a], &worlds[b]);
+        let order = compare_worlds(a, b, sort.column);
+        let order = if sort.ascending {
+            order
+        } else {
+            order.reverse()
+        };
+        is_favorite(b)
+            .cmp(&is_favorite(a))
+            .then(order)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    indices
+}
+
+/// Compares two worlds by a column; worlds still being scanned sort as smallest
+fn compare_worlds(a: &WorldInfo, b: &WorldInfo, column: SortColumn) -> Ordering {
+    let (a_details, b_details) = (a.details.as_ref(), b.details.as_ref());
+    match column {
+        SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        SortColumn::Size => a_details.map(|d| d.size).cmp(&b_details.map(|d| d.size)),
+        // Timestamps are formatted as "YYYY-MM-DD HH:MM:SS" and sort as text
+        SortColumn::LastPlayed => a_details
+            .and_then(|d| d.last_played.as_ref())
+            .cmp(&b_details.and_then(|d| d.last_played.as_ref())),
+        SortColumn::Backups => a_details
+            .map(|d| d.backup_count)
+            .cmp(&b_details.map(|d| d.backup_count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WorldDetails;
+
+    fn world(name: &str, size: u64, last_played: Option<&str>) -> WorldInfo {
+        WorldInfo {
+            name: name.to_string(),
+            path: PathBuf::from("/saves").join(name),
+            details: Some(WorldDetails {
+                size,
+                last_played: last_played.map(str::to_string),
+                backup_count: 0,
+            }),
+            source: "Hytale".to_string(),
+            server_dir: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_and_search() {
+        let worlds = vec![
+            world("beta", 300, Some("2026-01-10 12:00:00")),
+            world("Alpha", 100, None),
+            world("gamma", 200, Some("2026-01-12 08:00:00")),
+        ];
+        let by = |column, ascending| WorldSort { column, ascending };
+
+        assert_eq!(
+            visible_worlds(&worlds, "", by(SortColumn::Name, true), &[]),
+            vec![1, 0, 2]
+        );
+        assert_eq!(
+            visible_worlds(&worlds, "", by(SortColumn::Size, false), &[]),
+            vec![0, 2, 1]
+        );
+        assert_eq!(
+            visible_worlds(&worlds, "", by(SortColumn::LastPlayed, false), &[]),
+            vec![2, 0, 1]
+        );
+        assert_eq!(
+            visible_worlds(&worlds, " MM", by(SortColumn::Name, true), &[]),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_favorites_first() {
+        let worlds = vec![
+            world("a", 1, None),
+            world("b", 2, None),
+            world("c", 3, None),
+        ];
+        let favorites = vec![PathBuf::from("/saves/c")];
+
+        assert_eq!(
+            visible_worlds(&worlds, "", WorldSort::default(), &favorites),
+            vec![2, 0, 1]
+        );
+    }
+}
@@ -1,14 +1,34 @@
 use eframe::egui;
 use rust_i18n::t;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
+use zip::read::ZipFile;
+use zip::result::ZipError;
 use zip::write::FileOptions;
-use zip::{ZipArchive, ZipWriter};
+use zip::{AesMode, ZipArchive, ZipWriter, ZIP64_BYTES_THR};
+#[cfg(test)]
+use zip::ZIP64_ENTRY_THR;
 
-use crate::models::BackupProgress;
+use crate::consistency::FileState;
+use crate::manifest::{
+    is_metadata_entry, ExportManifest, ManifestEntry, BUNDLE_PATH, ENCRYPTED_MANIFEST_PATH,
+    MANIFEST_PATH, SERVER_CONFIG_DIR, SIGNATURE_PATH,
+};
+use crate::models::{
+    ArchiveInfo, BackupProgress, ExportOptions, ExportReport, ProgressStage, VerifyOutcome,
+};
+use crate::server::server_config_files;
+use crate::signing::{
+    check_signature, load_identity, load_trusted_keys, sign_manifest, ManifestSignature,
+    SignatureStatus,
+};
+use crate::snapshot::Snapshot;
+use crate::volumes::{VolumeReader, VolumeWriter};
 
 /// Gets the path to Hytale world saves
 pub fn get_hytale_worlds_path() -> Result<PathBuf, String> {
@@ -51,23 +71,39 @@ pub fn get_hytale_worlds_path() -> Result<PathBuf, String> {
     }
 }
 
+/// Files at least this large are written with ZIP64 headers. The margin below
+/// the 4 GB limit covers compression overhead on incompressible data.
+const LARGE_FILE_THRESHOLD: u64 = ZIP64_BYTES_THR - 64 * 1024 * 1024;
+
+/// Buffer size for streaming file contents into and out of archives
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
 /// Backs up a world to a ZIP file with progress tracking
 pub fn backup_world_to_path_with_progress(
-    world_name: &str,
-    zip_path: &PathBuf,
-    include_logs: bool,
-    include_backups: bool,
+    world_path: &Path,
+    zip_path: &Path,
+    options: &ExportOptions,
     progress: &Arc<Mutex<BackupProgress>>,
     ctx: &egui::Context,
-) -> Result<String, String> {
-    // Get the worlds directory
-    let worlds_path = get_hytale_worlds_path()?;
-    let world_path = worlds_path.join(world_name);
+) -> Result<ExportReport, String> {
+    let include_logs = options.include_logs;
+    let include_backups = options.include_backups;
+
+    let world_name = world_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
 
     if !world_path.exists() {
         return Err(t!("errors.world_not_found", name = world_name).to_string());
     }
 
+    let identity = if options.sign {
+        Some(load_identity().ok_or_else(|| t!("errors.no_signing_key").to_string())?)
+    } else {
+        None
+    };
+
     // Helper function to check if path should be excluded
     let should_exclude = |path: &std::path::Path| -> bool {
         let path_str = path.to_string_lossy();
@@ -80,31 +116,72 @@ pub fn backup_world_to_path_with_progress(
         false
     };
 
+    let server_configs = options
+        .server_dir
+        .as_deref()
+        .map(server_config_files)
+        .unwrap_or_default();
+
     // Count total files first (excluding filtered directories)
-    let total_files: usize = WalkDir::new(&world_path)
+    let world_files: usize = WalkDir::new(world_path)
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_file())
         .filter(|e| !should_exclude(e.path()))
         .count();
+    let total_files = world_files + server_configs.len();
+
+    // Take a quick snapshot first and archive from it, so the game can keep running
+    let mut changed_files = Vec::new();
+    let snapshot = if options.snapshot {
+        {
+            let mut p = progress.lock().unwrap();
+            p.stage = ProgressStage::Snapshotting;
+            p.total = world_files;
+        }
+        let mut current_count = 0;
+        let (snapshot, changed) = Snapshot::create(world_path, should_exclude, |name| {
+            current_count += 1;
+            let mut p = progress.lock().unwrap();
+            p.current = current_count;
+            p.current_file = name.to_string();
+            ctx.request_repaint();
+        })?;
+        changed_files = changed;
+        Some(snapshot)
+    } else {
+        None
+    };
+    let source_path = snapshot.as_ref().map_or(world_path, |snapshot| snapshot.path());
 
     {
         let mut p = progress.lock().unwrap();
+        p.stage = ProgressStage::Compressing;
+        p.current = 0;
         p.total = total_files;
     }
 
-    // Create the ZIP file
-    let file = File::create(zip_path)
+    // Create the ZIP file, split into volumes if a size cap is set
+    let file = VolumeWriter::create(zip_path, options.split_size)
         .map_err(|e| t!("errors.zip_create_failed", error = e.to_string()).to_string())?;
 
     let mut zip = ZipWriter::new(file);
-    let options = FileOptions::<()>::default()
-        .compression_method(zip::CompressionMethod::Deflated);
+    let dir_options = FileOptions::<()>::default()
+        .compression_method(options.compression.method())
+        .compression_level(options.compression_level);
+    // Only file entries are encrypted, directory entries carry no data
+    let file_options = match &options.password {
+        Some(password) => dir_options.with_aes_encryption(AesMode::Aes256, password),
+        None => dir_options,
+    };
 
     let mut current_count = 0;
+    // Hashes of all written files, used for verification and signing
+    let mut manifest = ExportManifest::new(&world_name);
+    manifest.world_id = options.world_id.clone();
 
-    // Walk through all files in the world directory
-    for entry in WalkDir::new(&world_path) {
+    // Walk through all files in the world directory (or its snapshot)
+    for entry in WalkDir::new(source_path) {
         let entry = entry.map_err(|e| t!("errors.read_files_failed", error = e.to_string()).to_string())?;
         let path = entry.path();
 
@@ -114,7 +191,7 @@ pub fn backup_world_to_path_with_progress(
         }
 
         let name = path
-            .strip_prefix(&world_path)
+            .strip_prefix(source_path)
             .map_err(|e| t!("errors.process_path_failed", error = e.to_string()).to_string())?;
 
         // Skip empty directory names
@@ -133,15 +210,12 @@ pub fn backup_world_to_path_with_progress(
             }
             ctx.request_repaint();
 
-            // Add file to ZIP
-            zip.start_file(name.to_string_lossy().to_string(), options)
-                .map_err(|e| t!("errors.add_file_failed", error = e.to_string()).to_string())?;
-
-            let file_content = fs::read(path)
-                .map_err(|e| t!("errors.read_file_failed", error = e.to_string()).to_string())?;
-
-            zip.write_all(&file_content)
-                .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+            let entry_name = name.to_string_lossy().to_string();
+            let (manifest_entry, changed) = add_file(&mut zip, path, entry_name, file_options)?;
+            if changed {
+                changed_files.push(manifest_entry.path.clone());
+            }
+            manifest.files.push(manifest_entry);
         } else if path.is_dir() {
             // Skip excluded directories entirely
             let name_str = name.to_string_lossy();
@@ -150,35 +224,633 @@ pub fn backup_world_to_path_with_progress(
             }
 
             // Add directory to ZIP
-            zip.add_directory(name.to_string_lossy().to_string(), options)
+            zip.add_directory(name.to_string_lossy().to_string(), dir_options)
                 .map_err(|e| t!("errors.add_dir_failed", error = e.to_string()).to_string())?;
         }
     }
 
-    zip.finish()
+    // Server config files are stored with the metadata and restored separately on import
+    for path in &server_configs {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let entry_name = format!("{}{}", SERVER_CONFIG_DIR, file_name);
+        current_count += 1;
+        {
+            let mut p = progress.lock().unwrap();
+            p.current = current_count;
+            p.current_file = file_name.to_string();
+        }
+        ctx.request_repaint();
+
+        let (manifest_entry, changed) = add_file(&mut zip, path, entry_name, file_options)?;
+        if changed {
+            changed_files.push(manifest_entry.path.clone());
+        }
+        manifest.files.push(manifest_entry);
+    }
+
+    // Store the manifest (and its signature) unencrypted so imports can check them up front.
+    // With a password only a stub is readable, and the encrypted manifest is signed instead
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+    let signed_json = if options.password.is_some() {
+        let stub_json = serde_json::to_vec_pretty(&manifest.stub())
+            .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+        write_metadata_entry(&mut zip, MANIFEST_PATH, &stub_json, dir_options)?;
+        let encrypted = encrypt_manifest(&manifest_json, file_options)?;
+        write_metadata_entry(
+            &mut zip,
+            ENCRYPTED_MANIFEST_PATH,
+            &encrypted,
+            dir_options.compression_method(zip::CompressionMethod::Stored),
+        )?;
+        encrypted
+    } else {
+        write_metadata_entry(&mut zip, MANIFEST_PATH, &manifest_json, dir_options)?;
+        manifest_json
+    };
+
+    let signed_by = match &identity {
+        Some(identity) => {
+            let signature = sign_manifest(identity, &signed_json)?;
+            let signature_json = serde_json::to_vec_pretty(&signature)
+                .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+            write_metadata_entry(&mut zip, SIGNATURE_PATH, &signature_json, dir_options)?;
+            Some(identity.name.clone())
+        }
+        None => None,
+    };
+
+    let volumes = zip.finish()
+        .and_then(|writer| writer.finish().map_err(Into::into))
         .map_err(|e| t!("errors.finish_zip_failed", error = e.to_string()).to_string())?;
+    let archive_path = volumes[0].clone();
+
+    let verification = if options.verify {
+        {
+            let mut p = progress.lock().unwrap();
+            p.stage = ProgressStage::Verifying;
+            p.current = 0;
+            p.total = manifest.files.len();
+            p.current_file = String::new();
+        }
+        ctx.request_repaint();
+        Some(verify_archive(
+            &archive_path,
+            &manifest.files,
+            options.password.as_deref(),
+            progress,
+            ctx,
+        ))
+    } else {
+        None
+    };
+
+    Ok(ExportReport {
+        path: archive_path,
+        volume_count: volumes.len(),
+        signed_by,
+        verification,
+        changed_files,
+    })
+}
+
+enum CopyError {
+    Read(std::io::Error),
+    Write(std::io::Error),
+}
+
+/// Streams data from a reader into a writer, returning the byte count and the
+/// hex-encoded SHA-256 hash of the data
+fn copy_hashed<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<(u64, String), CopyError> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut size = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(CopyError::Read(e)),
+        };
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read]).map_err(CopyError::Write)?;
+        size += read as u64;
+    }
+
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// Streams a file into the archive, returning its manifest entry and whether
+/// the file changed on disk while it was being copied
+fn add_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    path: &Path,
+    entry_name: String,
+    options: FileOptions<()>,
+) -> Result<(ManifestEntry, bool), String> {
+    let mut source = File::open(path)
+        .map_err(|e| t!("errors.read_file_failed", error = e.to_string()).to_string())?;
+    let state_before = FileState::from_metadata(
+        &source
+            .metadata()
+            .map_err(|e| t!("errors.read_file_failed", error = e.to_string()).to_string())?,
+    );
+
+    // ZIP64 headers are needed for files near or above 4 GB
+    zip.start_file(
+        entry_name.clone(),
+        options.large_file(state_before.len >= LARGE_FILE_THRESHOLD),
+    )
+    .map_err(|e| t!("errors.add_file_failed", error = e.to_string()).to_string())?;
+
+    let (size, sha256) = copy_hashed(&mut source, zip).map_err(|e| match e {
+        CopyError::Read(e) => t!("errors.read_file_failed", error = e.to_string()).to_string(),
+        CopyError::Write(e) => t!("errors.write_zip_failed", error = e.to_string()).to_string(),
+    })?;
+
+    // The game may have written to the file while it was being copied
+    let changed = size != state_before.len || FileState::read(path) != Some(state_before);
+
+    Ok((ManifestEntry { path: entry_name, size, sha256 }, changed))
+}
+
+/// Adds a metadata file to the archive
+fn write_metadata_entry<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    contents: &[u8],
+    options: FileOptions<()>,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| t!("errors.add_file_failed", error = e.to_string()).to_string())?;
+    zip.write_all(contents)
+        .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())
+}
+
+/// Encrypts the manifest into a small ZIP of its own
+///
+/// The signature has to cover the encrypted bytes, which an entry of the
+/// archive only has once it is written, so the manifest is encrypted up front.
+fn encrypt_manifest(manifest_json: &[u8], options: FileOptions<()>) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    write_metadata_entry(&mut zip, "manifest.json", manifest_json, options)?;
+    zip.finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| t!("errors.finish_zip_failed", error = e.to_string()).to_string())
+}
+
+/// Decrypts a manifest encrypted by [`encrypt_manifest`]
+fn decrypt_manifest(encrypted: &[u8], password: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut archive = ZipArchive::new(Cursor::new(encrypted))
+        .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+    let mut entry = open_entry(&mut archive, 0, password)?;
+    let mut contents = Vec::new();
+    entry.read_to_end(&mut contents)
+        .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+    Ok(contents)
+}
+
+/// Re-opens a finished archive and compares every entry against the hash of
+/// the source data that was written for it
+fn verify_archive(
+    zip_path: &Path,
+    expected: &[ManifestEntry],
+    password: Option<&str>,
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> VerifyOutcome {
+    match read_back_archive(zip_path, expected, password, progress, ctx) {
+        Ok(count) => VerifyOutcome::Verified(count),
+        Err(e) => VerifyOutcome::Failed(e),
+    }
+}
+
+/// Verifies an existing archive against its manifest
+pub fn verify_existing_archive(
+    zip_path: &Path,
+    manifest: &ExportManifest,
+    password: Option<&str>,
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> VerifyOutcome {
+    {
+        let mut p = progress.lock().unwrap();
+        p.stage = ProgressStage::Verifying;
+        p.current = 0;
+        p.total = manifest.files.len();
+    }
+    verify_archive(zip_path, &manifest.files, password, progress, ctx)
+}
+
+fn read_back_archive(
+    zip_path: &Path,
+    expected: &[ManifestEntry],
+    password: Option<&str>,
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> Result<usize, String> {
+    let file = VolumeReader::open(zip_path)
+        .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+
+    for (index, expected_entry) in expected.iter().enumerate() {
+        let name = &expected_entry.path;
+        {
+            let mut p = progress.lock().unwrap();
+            p.current = index + 1;
+            p.current_file = name.clone();
+        }
+        ctx.request_repaint();
+
+        let index = archive
+            .index_for_name(name)
+            .ok_or_else(|| t!("errors.verify_missing_entry", name = name).to_string())?;
+        let mut entry = open_entry(&mut archive, index, password)?;
+
+        // Reading to the end also checks the CRC stored in the archive
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut entry, &mut hasher).map_err(|e| {
+            t!("errors.verify_read_failed", name = name, error = e.to_string()).to_string()
+        })?;
+
+        if hex::encode(hasher.finalize()) != expected_entry.sha256 {
+            return Err(t!("errors.verify_mismatch", name = name).to_string());
+        }
+    }
+
+    Ok(expected.len())
+}
+
+/// Reads the metadata of an archive before importing it: whether it is
+/// encrypted, its manifest and the state of the manifest signature
+///
+/// If the manifest is encrypted, only its stub is read here and the signature
+/// is checked against the encrypted manifest; see [`unlock_manifest`].
+pub fn inspect_archive(zip_path: &Path) -> Result<ArchiveInfo, String> {
+    let file = VolumeReader::open(zip_path)
+        .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+
+    let mut encrypted = false;
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)
+            .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+        encrypted |= entry.encrypted();
+    }
+
+    let manifest_json = read_metadata_entry(&mut archive, MANIFEST_PATH)?;
+    let encrypted_manifest = read_metadata_entry(&mut archive, ENCRYPTED_MANIFEST_PATH)?;
+    let signature: Option<ManifestSignature> = read_metadata_entry(&mut archive, SIGNATURE_PATH)?
+        .and_then(|json| serde_json::from_slice(&json).ok());
+
+    let Some(manifest_json) = manifest_json else {
+        if archive.index_for_name(BUNDLE_PATH).is_some() {
+            return Err(t!("errors.bundle_archive").to_string());
+        }
+        // A signature without a manifest means the manifest was stripped
+        let signature = if signature.is_some() {
+            SignatureStatus::Tampered
+        } else {
+            SignatureStatus::Unsigned
+        };
+        return Ok(ArchiveInfo {
+            encrypted,
+            manifest: None,
+            encrypted_manifest: None,
+            signature,
+        });
+    };
+
+    let manifest: Option<ExportManifest> = serde_json::from_slice(&manifest_json).ok();
+    let signed_json = encrypted_manifest.as_deref().unwrap_or(&manifest_json);
+    let mut signature = check_signature(signed_json, signature.as_ref(), &load_trusted_keys());
+
+    // The file contents are checked against the manifest hashes while extracting;
+    // here only the listing is compared, which is cheap and needs no password.
+    // An encrypted manifest's listing is compared once it is decrypted
+    if signature != SignatureStatus::Unsigned
+        && encrypted_manifest.is_none()
+        && !manifest
+            .as_ref()
+            .is_some_and(|manifest| listing_matches_manifest(&mut archive, manifest))
+    {
+        signature = SignatureStatus::Tampered;
+    }
+
+    Ok(ArchiveInfo {
+        encrypted,
+        manifest,
+        encrypted_manifest,
+        signature,
+    })
+}
+
+/// Returns the full manifest of an archive, decrypting it if it is encrypted
+///
+/// A signed encrypted manifest must match its stub and the listing of the
+/// archive, as an unencrypted one is checked in [`inspect_archive`].
+pub fn unlock_manifest(
+    zip_path: &Path,
+    info: &ArchiveInfo,
+    password: Option<&str>,
+) -> Result<Option<ExportManifest>, String> {
+    let Some(encrypted_manifest) = &info.encrypted_manifest else {
+        return Ok(info.manifest.clone());
+    };
+
+    let manifest_json = decrypt_manifest(encrypted_manifest, password)?;
+    let manifest: ExportManifest = serde_json::from_slice(&manifest_json)
+        .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+
+    if info.signature != SignatureStatus::Unsigned {
+        let file = VolumeReader::open(zip_path)
+            .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
+        let mut archive = ZipArchive::new(file)
+            .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+        if info.manifest.as_ref() != Some(&manifest.stub())
+            || !listing_matches_manifest(&mut archive, &manifest)
+        {
+            return Err(t!("errors.archive_tampered").to_string());
+        }
+    }
+
+    Ok(Some(manifest))
+}
+
+/// Reads a metadata file from the archive, if present
+fn read_metadata_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let Some(index) = archive.index_for_name(name) else {
+        return Ok(None);
+    };
+
+    let mut entry = archive.by_index(index)
+        .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+    let mut contents = Vec::new();
+    entry.read_to_end(&mut contents)
+        .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+
+    Ok(Some(contents))
+}
+
+/// Checks that the archive contains exactly the files listed in the manifest
+fn listing_matches_manifest<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    manifest: &ExportManifest,
+) -> bool {
+    let expected: HashMap<&str, u64> = manifest
+        .files
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry.size))
+        .collect();
+
+    let mut file_count = 0;
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index_raw(i) else {
+            return false;
+        };
+        // Everything but the manifest and its signature is listed, including server configs
+        if entry.is_dir()
+            || entry.name() == MANIFEST_PATH
+            || entry.name() == ENCRYPTED_MANIFEST_PATH
+            || entry.name() == SIGNATURE_PATH
+        {
+            continue;
+        }
+        if expected.get(entry.name()) != Some(&entry.size()) {
+            return false;
+        }
+        file_count += 1;
+    }
 
-    Ok(zip_path.to_string_lossy().to_string())
+    file_count == expected.len()
+}
+
+/// Opens an archive entry for reading, decrypting it if it is encrypted
+fn open_entry<'a, R: Read + Seek>(
+    archive: &'a mut ZipArchive<R>,
+    index: usize,
+    password: Option<&str>,
+) -> Result<ZipFile<'a>, String> {
+    let encrypted = archive.by_index_raw(index)
+        .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?
+        .encrypted();
+
+    let entry = match (encrypted, password) {
+        (true, Some(password)) => archive.by_index_decrypt(index, password.as_bytes()),
+        (true, None) => return Err(t!("errors.password_required").to_string()),
+        (false, _) => archive.by_index(index),
+    };
+
+    entry.map_err(|e| match e {
+        ZipError::InvalidPassword => t!("errors.wrong_password").to_string(),
+        e => t!("errors.read_zip_entry_failed", error = e.to_string()).to_string(),
+    })
 }
 
 /// Imports a world from a ZIP file
-pub fn import_world(zip_path: &PathBuf, world_name: &str) -> Result<(), String> {
-    // Get the saves directory
-    let saves_path = get_hytale_worlds_path()?;
-    let world_path = saves_path.join(world_name);
-
-    // If the world folder exists, delete it first
-    if world_path.exists() {
-        fs::remove_dir_all(&world_path)
+///
+/// The archive is extracted into a staging folder next to the world first, so
+/// a wrong password or a damaged archive leaves an existing world untouched.
+///
+/// With a server directory, server config files stored in the archive are
+/// restored into it as well.
+pub fn import_world(
+    zip_path: &Path,
+    world_path: &Path,
+    password: Option<&str>,
+    server_dir: Option<&Path>,
+) -> Result<(), String> {
+    let (staging_path, info) = stage_archive(zip_path, world_path, password)?;
+
+    // Read and check the server configs before anything existing is replaced
+    let server_configs = match (server_dir, &info.manifest) {
+        (Some(_), Some(manifest)) => match read_server_configs(zip_path, password, manifest) {
+            Ok(configs) => configs,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging_path);
+                return Err(e);
+            }
+        },
+        _ => Vec::new(),
+    };
+
+    if let Err(e) = replace_world(&staging_path, world_path, &[]) {
+        let _ = fs::remove_dir_all(&staging_path);
+        return Err(e);
+    }
+
+    if let Some(server_dir) = server_dir {
+        for (file_name, contents) in server_configs {
+            fs::write(server_dir.join(file_name), contents)
+                .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves a new copy of a world into place, replacing the existing one
+///
+/// The existing world is renamed to a hidden sibling first and only deleted
+/// once the new copy is in place, so a failure at any step puts it back as it
+/// was. The folders named in `kept` are taken over from the existing world
+/// instead of the new copy.
+pub fn replace_world(new_path: &Path, world_path: &Path, kept: &[&str]) -> Result<(), String> {
+    let replace_error = |e: std::io::Error| t!("errors.replace_world_failed", error = e.to_string()).to_string();
+
+    let mut old_name = std::ffi::OsString::from(".");
+    old_name.push(world_path.file_name().unwrap_or_default());
+    old_name.push(".replaced");
+    let old_path = world_path.with_file_name(old_name);
+
+    // A leftover from an earlier run is the only copy if the world is missing
+    if old_path.exists() {
+        if world_path.exists() {
+            fs::remove_dir_all(&old_path).map_err(replace_error)?;
+        } else {
+            fs::rename(&old_path, world_path).map_err(replace_error)?;
+        }
+    }
+
+    if !world_path.exists() {
+        return fs::rename(new_path, world_path).map_err(replace_error);
+    }
+
+    let kept: Vec<&str> = kept
+        .iter()
+        .copied()
+        .filter(|name| world_path.join(name).is_dir())
+        .collect();
+    for name in &kept {
+        let replaced = new_path.join(name);
+        if replaced.exists() {
+            fs::remove_dir_all(&replaced).map_err(replace_error)?;
+        }
+    }
+
+    fs::rename(world_path, &old_path).map_err(replace_error)?;
+    if let Err(e) = fs::rename(new_path, world_path) {
+        let _ = fs::rename(&old_path, world_path);
+        return Err(replace_error(e));
+    }
+    for (index, name) in kept.iter().enumerate() {
+        if let Err(e) = fs::rename(old_path.join(name), world_path.join(name)) {
+            for moved in &kept[..index] {
+                let _ = fs::rename(world_path.join(moved), old_path.join(moved));
+            }
+            let _ = fs::rename(world_path, new_path);
+            let _ = fs::rename(&old_path, world_path);
+            return Err(replace_error(e));
+        }
+    }
+
+    // The new world is in place; a leftover old copy is removed on the next run
+    let _ = fs::remove_dir_all(&old_path);
+    Ok(())
+}
+
+/// Extracts an archive into a hidden staging folder next to a world
+///
+/// The archive is checked against its signature and manifest on the way.
+/// Returns the staging folder, which the caller moves into place, and the
+/// metadata of the archive with its manifest decrypted.
+pub fn stage_archive(
+    zip_path: &Path,
+    world_path: &Path,
+    password: Option<&str>,
+) -> Result<(PathBuf, ArchiveInfo), String> {
+    let mut staging_name = std::ffi::OsString::from(".");
+    staging_name.push(world_path.file_name().unwrap_or_default());
+    staging_name.push(".importing");
+    let staging_path = world_path.with_file_name(staging_name);
+
+    if staging_path.exists() {
+        fs::remove_dir_all(&staging_path)
             .map_err(|e| t!("errors.delete_world_failed", error = e.to_string()).to_string())?;
     }
 
+    let mut info = inspect_archive(zip_path)?;
+    if info.signature == SignatureStatus::Tampered {
+        return Err(t!("errors.archive_tampered").to_string());
+    }
+    info.manifest = unlock_manifest(zip_path, &info, password)?;
+    info.encrypted_manifest = None;
+
+    if let Err(e) = extract_archive(zip_path, &staging_path, password, info.manifest.as_ref()) {
+        let _ = fs::remove_dir_all(&staging_path);
+        return Err(e);
+    }
+
+    Ok((staging_path, info))
+}
+
+/// Reads the server config files listed in the manifest and checks their hashes
+fn read_server_configs(
+    zip_path: &Path,
+    password: Option<&str>,
+    manifest: &ExportManifest,
+) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let file = VolumeReader::open(zip_path)
+        .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+
+    let mut configs = Vec::new();
+    for expected in manifest.server_configs() {
+        let file_name = &expected.path[SERVER_CONFIG_DIR.len()..];
+        // Configs live in the top level of the server directory
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name == ".." {
+            return Err(t!("errors.entry_tampered", name = expected.path).to_string());
+        }
+
+        let index = archive.index_for_name(&expected.path)
+            .ok_or_else(|| t!("errors.verify_missing_entry", name = expected.path).to_string())?;
+        let mut entry = open_entry(&mut archive, index, password)?;
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)
+            .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+
+        if hex::encode(Sha256::digest(&contents)) != expected.sha256 {
+            return Err(t!("errors.entry_tampered", name = expected.path).to_string());
+        }
+        configs.push((file_name.to_string(), contents));
+    }
+
+    Ok(configs)
+}
+
+/// Extracts all entries of a ZIP file into the given directory
+///
+/// With a manifest, every extracted file is hashed and compared against it.
+fn extract_archive(
+    zip_path: &Path,
+    world_path: &Path,
+    password: Option<&str>,
+    manifest: Option<&ExportManifest>,
+) -> Result<(), String> {
+    let expected_hashes: Option<HashMap<&str, &str>> = manifest.map(|manifest| {
+        manifest
+            .files
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.sha256.as_str()))
+            .collect()
+    });
+
     // Create the world directory
-    fs::create_dir_all(&world_path)
+    fs::create_dir_all(world_path)
         .map_err(|e| t!("errors.create_dir_failed", error = e.to_string()).to_string())?;
 
     // Open the ZIP file
-    let file = File::open(zip_path)
+    let file = VolumeReader::open(zip_path)
         .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
 
     let mut archive = ZipArchive::new(file)
@@ -186,8 +858,12 @@ pub fn import_world(zip_path: &PathBuf, world_name: &str) -> Result<(), String>
 
     // Extract all files
     for i in 0..archive.len() {
-        let mut file = archive.by_index(i)
-            .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+        let mut file = open_entry(&mut archive, i, password)?;
+
+        // Export metadata is not part of the world
+        if is_metadata_entry(file.name()) {
+            continue;
+        }
 
         let outpath = match file.enclosed_name() {
             Some(path) => world_path.join(path),
@@ -211,15 +887,318 @@ pub fn import_world(zip_path: &PathBuf, world_name: &str) -> Result<(), String>
             let mut outfile = File::create(&outpath)
                 .map_err(|e| t!("errors.create_file_failed", error = e.to_string()).to_string())?;
 
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)
-                .map_err(|e| t!("errors.read_zip_entry_failed", error = e.to_string()).to_string())?;
+            let (_, hash) = copy_hashed(&mut file, &mut outfile).map_err(|e| match e {
+                CopyError::Read(e) => t!("errors.read_zip_entry_failed", error = e.to_string()).to_string(),
+                CopyError::Write(e) => t!("errors.write_file_failed", error = e.to_string()).to_string(),
+            })?;
 
-            outfile.write_all(&buffer)
-                .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+            if let Some(expected_hashes) = &expected_hashes {
+                if expected_hashes.get(file.name()) != Some(&hash.as_str()) {
+                    return Err(t!("errors.entry_tampered", name = file.name()).to_string());
+                }
+            }
         }
     }
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Compression;
+
+    fn export(world_path: &Path, zip_path: &Path, options: &ExportOptions) -> ExportReport {
+        let progress = Arc::new(Mutex::new(BackupProgress::default()));
+        backup_world_to_path_with_progress(
+            world_path,
+            zip_path,
+            options,
+            &progress,
+            &egui::Context::default(),
+        )
+        .unwrap()
+    }
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            include_logs: true,
+            include_backups: true,
+            verify: true,
+            password: None,
+            split_size: None,
+            sign: false,
+            snapshot: false,
+            server_dir: None,
+            compression: Compression::Deflated,
+            compression_level: None,
+            world_id: None,
+        }
+    }
+
+    /// Deterministic, incompressible test data
+    fn noise(len: usize, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn count_files(path: &Path) -> usize {
+        WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .count()
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(world.join("chunks/region")).unwrap();
+        fs::create_dir_all(world.join("logs")).unwrap();
+        fs::write(world.join("config.json"), b"{\"seed\": 42}").unwrap();
+        fs::write(world.join("chunks/region/0.0.region"), noise(200_000, 1)).unwrap();
+        fs::write(world.join("logs/2026-01-13_19-35-06_server.log"), b"log").unwrap();
+
+        let zip_path = dir.path().join("World.zip");
+        let report = export(
+            &world,
+            &zip_path,
+            &ExportOptions {
+                include_logs: false,
+                ..options()
+            },
+        );
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(2)));
+
+        let info = inspect_archive(&zip_path).unwrap();
+        assert!(!info.encrypted);
+        assert_eq!(info.manifest.unwrap().world_name, "World");
+
+        let imported = dir.path().join("Imported");
+        import_world(&zip_path, &imported, None, None).unwrap();
+        assert_eq!(
+            fs::read(imported.join("chunks/region/0.0.region")).unwrap(),
+            noise(200_000, 1)
+        );
+        assert!(!imported.join("logs").exists());
+        assert!(!imported.join(".hytale-export").exists());
+    }
+
+    #[test]
+    fn test_replace_world() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(world.join("backup")).unwrap();
+        fs::write(world.join("config.json"), "old").unwrap();
+        fs::write(world.join("backup/1.zip"), "backup").unwrap();
+
+        // A failed swap leaves the existing world as it was
+        let missing = dir.path().join("missing");
+        assert!(replace_world(&missing, &world, &["backup"]).is_err());
+        assert_eq!(fs::read_to_string(world.join("config.json")).unwrap(), "old");
+        assert!(world.join("backup/1.zip").exists());
+        assert!(!dir.path().join(".World.replaced").exists());
+
+        let new = dir.path().join(".World.importing");
+        fs::create_dir_all(new.join("backup")).unwrap();
+        fs::write(new.join("config.json"), "new").unwrap();
+        replace_world(&new, &world, &["backup", "logs"]).unwrap();
+        assert_eq!(fs::read_to_string(world.join("config.json")).unwrap(), "new");
+        assert!(world.join("backup/1.zip").exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_zstd_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(&world).unwrap();
+        fs::write(world.join("config.json"), b"{\"seed\": 42}").unwrap();
+
+        let zip_path = dir.path().join("World.zip");
+        let report = export(
+            &world,
+            &zip_path,
+            &ExportOptions {
+                compression: Compression::Zstd,
+                compression_level: Some(19),
+                ..options()
+            },
+        );
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(1)));
+
+        let imported = dir.path().join("Imported");
+        import_world(&zip_path, &imported, None, None).unwrap();
+        assert_eq!(fs::read(imported.join("config.json")).unwrap(), b"{\"seed\": 42}");
+    }
+
+    #[test]
+    fn test_snapshot_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(world.join("chunks")).unwrap();
+        fs::write(world.join("chunks/0.0.region"), noise(100_000, 4)).unwrap();
+
+        let zip_path = dir.path().join("World.zip");
+        let report = export(
+            &world,
+            &zip_path,
+            &ExportOptions {
+                snapshot: true,
+                ..options()
+            },
+        );
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(1)));
+        assert!(report.changed_files.is_empty());
+        assert!(!Snapshot::staging_path(&world).exists());
+
+        // The manifest is named after the world, not the staging folder
+        let info = inspect_archive(&zip_path).unwrap();
+        assert_eq!(info.manifest.unwrap().world_name, "World");
+    }
+
+    #[test]
+    fn test_server_config_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = dir.path().join("server");
+        let universe = server.join("universe");
+        fs::create_dir_all(universe.join("worlds/default")).unwrap();
+        fs::write(universe.join("worlds/default/config.json"), b"{}").unwrap();
+        fs::write(server.join("config.json"), b"{\"MaxPlayers\": 20}").unwrap();
+
+        let zip_path = dir.path().join("universe.zip");
+        let report = export(
+            &universe,
+            &zip_path,
+            &ExportOptions {
+                server_dir: Some(server.clone()),
+                sign: false,
+                ..options()
+            },
+        );
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(2)));
+
+        // Restoring into another server brings the config along, outside the universe
+        let restored = dir.path().join("restored");
+        fs::create_dir_all(&restored).unwrap();
+        import_world(&zip_path, &restored.join("universe"), None, Some(&restored)).unwrap();
+        assert_eq!(
+            fs::read(restored.join("config.json")).unwrap(),
+            b"{\"MaxPlayers\": 20}"
+        );
+        assert!(restored.join("universe/worlds/default/config.json").exists());
+        assert!(!restored.join("universe/.hytale-export").exists());
+
+        // A plain import ignores the server config
+        let world = dir.path().join("World");
+        import_world(&zip_path, &world, None, None).unwrap();
+        assert!(!dir.path().join("config.json").exists());
+    }
+
+    #[test]
+    fn test_encrypted_split_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(&world).unwrap();
+        fs::write(world.join("a.bin"), noise(150_000, 2)).unwrap();
+        fs::write(world.join("b.bin"), noise(150_000, 3)).unwrap();
+
+        let zip_path = dir.path().join("World.zip");
+        let report = export(
+            &world,
+            &zip_path,
+            &ExportOptions {
+                password: Some("secret".to_string()),
+                split_size: Some(64 * 1024),
+                ..options()
+            },
+        );
+        assert!(report.volume_count > 1);
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(2)));
+
+        // Without the password only the world's name is readable, not its file list
+        let info = inspect_archive(&report.path).unwrap();
+        assert!(info.encrypted);
+        let stub = info.manifest.clone().unwrap();
+        assert_eq!(stub.world_name, "World");
+        assert!(stub.files.is_empty());
+        assert!(unlock_manifest(&report.path, &info, None).is_err());
+        assert!(unlock_manifest(&report.path, &info, Some("wrong")).is_err());
+        let manifest = unlock_manifest(&report.path, &info, Some("secret"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(manifest.stub(), stub);
+        assert_eq!(manifest.files.len(), 2);
+
+        // A wrong password must leave an existing world untouched
+        let imported = dir.path().join("Imported");
+        fs::create_dir_all(&imported).unwrap();
+        fs::write(imported.join("keep.txt"), b"old").unwrap();
+        assert!(import_world(&report.path, &imported, Some("wrong"), None).is_err());
+        assert!(imported.join("keep.txt").exists());
+
+        import_world(&report.path, &imported, Some("secret"), None).unwrap();
+        assert!(!imported.join("keep.txt").exists());
+        assert_eq!(fs::read(imported.join("b.bin")).unwrap(), noise(150_000, 3));
+    }
+
+    #[test]
+    fn test_more_than_65535_entries_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        let file_count = ZIP64_ENTRY_THR + 100;
+        for i in 0..file_count {
+            let folder = world.join(format!("chunks/{}", i / 1000));
+            if i % 1000 == 0 {
+                fs::create_dir_all(&folder).unwrap();
+            }
+            fs::write(folder.join(format!("{}.chunk", i)), i.to_le_bytes()).unwrap();
+        }
+
+        let zip_path = dir.path().join("World.zip");
+        export(
+            &world,
+            &zip_path,
+            &ExportOptions {
+                verify: false,
+                ..options()
+            },
+        );
+
+        let imported = dir.path().join("Imported");
+        import_world(&zip_path, &imported, None, None).unwrap();
+        assert_eq!(count_files(&imported), file_count);
+        assert_eq!(
+            fs::read(imported.join("chunks/65/65535.chunk")).unwrap(),
+            65535usize.to_le_bytes()
+        );
+    }
+
+    /// Writes and extracts more than 4 GB, run explicitly with `cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_huge_sparse_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(&world).unwrap();
+        let huge_size = ZIP64_BYTES_THR + 1024 * 1024;
+        let huge = File::create(world.join("huge.region")).unwrap();
+        huge.set_len(huge_size).unwrap();
+        drop(huge);
+        fs::write(world.join("small.json"), b"{}").unwrap();
+
+        let zip_path = dir.path().join("World.zip");
+        let report = export(&world, &zip_path, &options());
+        assert_eq!(report.verification, Some(VerifyOutcome::Verified(2)));
+
+        let imported = dir.path().join("Imported");
+        import_world(&zip_path, &imported, None, None).unwrap();
+        assert_eq!(fs::metadata(imported.join("huge.region")).unwrap().len(), huge_size);
+    }
+}
@@ -0,0 +1,297 @@
+//! Exporting several worlds in one run
+//!
+//! Worlds are exported one after another with the same options, either to one
+//! archive per world or into a bundle. A bundle is an uncompressed ZIP holding
+//! the archive of each world plus a listing, so every world keeps its own
+//! manifest and signature and can be imported once the bundle is extracted.
+
+use eframe::egui;
+use rust_i18n::t;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter, ZIP64_BYTES_THR};
+
+use crate::backup::backup_world_to_path_with_progress;
+use crate::manifest::{BundleManifest, BundleWorld, BUNDLE_PATH, MANIFEST_VERSION};
+use crate::models::{BackupProgress, BatchResult, ExportOptions};
+use crate::volumes::volume_path;
+
+/// A world to export and the file name of its archive
+pub struct BatchJob {
+    pub world_name: String,
+    pub world_path: PathBuf,
+    pub file_name: String,
+    pub options: ExportOptions,
+}
+
+/// Exports the worlds into a folder, or into a bundle in that folder when a
+/// bundle file name is given
+///
+/// A world that fails does not stop the others; the error is part of its
+/// result. Archives inside a bundle are never split.
+pub fn export_batch(
+    jobs: &[BatchJob],
+    directory: &Path,
+    bundle_name: Option<&str>,
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> Result<Vec<BatchResult>, String> {
+    let staging = bundle_name.map(|name| directory.join(format!(".{}.staging", name)));
+    if let Some(staging) = &staging {
+        fs::create_dir_all(staging)
+            .map_err(|e| t!("errors.zip_create_failed", error = e.to_string()).to_string())?;
+    }
+
+    let mut results = Vec::new();
+    for (index, job) in jobs.iter().enumerate() {
+        {
+            let mut p = progress.lock().unwrap();
+            p.world_index = index;
+            p.world_count = jobs.len();
+            p.world_name = job.world_name.clone();
+        }
+
+        let mut options = job.options.clone();
+        let target = match &staging {
+            Some(staging) => {
+                options.split_size = None;
+                staging.join(&job.file_name)
+            }
+            None => directory.join(&job.file_name),
+        };
+        let result =
+            backup_world_to_path_with_progress(&job.world_path, &target, &options, progress, ctx);
+        results.push(BatchResult {
+            world_name: job.world_name.clone(),
+            result,
+        });
+    }
+
+    if let (Some(staging), Some(name)) = (&staging, bundle_name) {
+        let bundle_path = directory.join(name);
+        let written = write_bundle(&bundle_path, &mut results, progress, ctx);
+        let _ = fs::remove_dir_all(staging);
+        if written.is_err() {
+            let _ = fs::remove_file(&bundle_path);
+        }
+        written?;
+    }
+
+    Ok(results)
+}
+
+/// Adds `-2`, `-3`, ... to an archive name until it is not taken
+///
+/// A name is taken if it is in `taken` or, with a directory, if the archive or
+/// its first volume already exists there. A batch has no save dialog to ask
+/// before overwriting.
+pub fn unique_file_name(file_name: &str, directory: Option<&Path>, taken: &[&str]) -> String {
+    let is_taken = |name: &str| {
+        taken.contains(&name)
+            || directory.is_some_and(|directory| {
+                let path = directory.join(name);
+                path.exists() || volume_path(&path, 1).exists()
+            })
+    };
+    let stem = file_name.strip_suffix(".zip").unwrap_or(file_name);
+    let mut name = file_name.to_string();
+    let mut counter = 2;
+    while is_taken(&name) {
+        name = format!("{}-{}.zip", stem, counter);
+        counter += 1;
+    }
+    name
+}
+
+/// Stores the successfully exported archives in a bundle and points their reports at it
+fn write_bundle(
+    bundle_path: &Path,
+    results: &mut [BatchResult],
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> Result<(), String> {
+    let file = File::create(bundle_path)
+        .map_err(|e| t!("errors.zip_create_failed", error = e.to_string()).to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let stored = FileOptions::<()>::default().compression_method(CompressionMethod::Stored);
+
+    let mut listing = BundleManifest {
+        format_version: MANIFEST_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        worlds: Vec::new(),
+    };
+
+    for result in results.iter_mut() {
+        let Ok(report) = &mut result.result else {
+            continue;
+        };
+        let archive = report
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        {
+            let mut p = progress.lock().unwrap();
+            p.current_file = archive.clone();
+        }
+        ctx.request_repaint();
+
+        let mut source = File::open(&report.path)
+            .map_err(|e| t!("errors.read_files_failed", error = e.to_string()).to_string())?;
+        let len = source
+            .metadata()
+            .map_err(|e| t!("errors.read_files_failed", error = e.to_string()).to_string())?
+            .len();
+        zip.start_file(archive.as_str(), stored.large_file(len >= ZIP64_BYTES_THR))
+            .map_err(|e| t!("errors.add_file_failed", error = e.to_string()).to_string())?;
+        io::copy(&mut source, &mut zip)
+            .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+
+        listing.worlds.push(BundleWorld {
+            world_name: result.world_name.clone(),
+            archive,
+        });
+        report.path = bundle_path.to_path_buf();
+    }
+
+    let listing_json = serde_json::to_vec_pretty(&listing)
+        .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+    zip.start_file(BUNDLE_PATH, stored)
+        .map_err(|e| t!("errors.add_file_failed", error = e.to_string()).to_string())?;
+    io::Write::write_all(&mut zip, &listing_json)
+        .map_err(|e| t!("errors.write_zip_failed", error = e.to_string()).to_string())?;
+    zip.finish()
+        .map_err(|e| t!("errors.finish_zip_failed", error = e.to_string()).to_string())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{import_world, inspect_archive};
+    use crate::models::Compression;
+    use zip::ZipArchive;
+
+    /// Incompressible world data, so a small split size yields several volumes
+    fn region_data(name: &str) -> Vec<u8> {
+        let mut state = name.len() as u32;
+        (0..20_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn job(dir: &Path, name: &str) -> BatchJob {
+        let world_path = dir.join("saves").join(name);
+        fs::create_dir_all(world_path.join("chunks")).unwrap();
+        fs::write(world_path.join("chunks/0.region"), region_data(name)).unwrap();
+        BatchJob {
+            world_name: name.to_string(),
+            world_path,
+            file_name: format!("{}.zip", name),
+            options: ExportOptions {
+                include_logs: true,
+                include_backups: true,
+                verify: true,
+                password: None,
+                split_size: Some(1024),
+                sign: false,
+                snapshot: false,
+                server_dir: None,
+                compression: Compression::Deflated,
+                compression_level: None,
+                world_id: None,
+            },
+        }
+    }
+
+    fn run(jobs: &[BatchJob], directory: &Path, bundle: Option<&str>) -> Vec<BatchResult> {
+        let progress = Arc::new(Mutex::new(BackupProgress::default()));
+        export_batch(
+            jobs,
+            directory,
+            bundle,
+            &progress,
+            &egui::Context::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_one_archive_per_world() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        fs::create_dir(&out).unwrap();
+        let mut missing = job(dir.path(), "Missing");
+        missing.world_path = dir.path().join("does-not-exist");
+        let jobs = vec![job(dir.path(), "Alpha"), missing, job(dir.path(), "Beta")];
+
+        let results = run(&jobs, &out, None);
+        assert_eq!(results.len(), 3);
+        assert!(results[1].result.is_err());
+        for result in [&results[0], &results[2]] {
+            let report = result.result.as_ref().unwrap();
+            assert!(report.volume_count > 1);
+            assert!(report.path.exists());
+        }
+    }
+
+    #[test]
+    fn test_unique_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("World.zip"), "").unwrap();
+        fs::write(dir.path().join("World-2.zip.001"), "").unwrap();
+
+        assert_eq!(
+            unique_file_name("Other.zip", Some(dir.path()), &[]),
+            "Other.zip"
+        );
+        assert_eq!(unique_file_name("World.zip", None, &[]), "World.zip");
+        assert_eq!(
+            unique_file_name("World.zip", Some(dir.path()), &["World-3.zip"]),
+            "World-4.zip"
+        );
+    }
+
+    #[test]
+    fn test_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        fs::create_dir(&out).unwrap();
+        let jobs = vec![job(dir.path(), "Alpha"), job(dir.path(), "Beta")];
+
+        let results = run(&jobs, &out, Some("worlds.zip"));
+        let bundle = out.join("worlds.zip");
+        assert!(results
+            .iter()
+            .all(|result| result.result.as_ref().unwrap().path == bundle));
+        // Only the bundle is left behind
+        assert_eq!(fs::read_dir(&out).unwrap().count(), 1);
+        assert!(inspect_archive(&bundle).is_err());
+
+        // Each world's archive can be imported after extracting the bundle
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let listing: BundleManifest =
+            serde_json::from_reader(archive.by_name(BUNDLE_PATH).unwrap()).unwrap();
+        assert_eq!(listing.worlds.len(), 2);
+        let extracted = dir.path().join("Beta.zip");
+        io::copy(
+            &mut archive.by_name(&listing.worlds[1].archive).unwrap(),
+            &mut File::create(&extracted).unwrap(),
+        )
+        .unwrap();
+        let imported = dir.path().join("imported");
+        import_world(&extracted, &imported, None, None).unwrap();
+        assert_eq!(
+            fs::read(imported.join("chunks/0.region")).unwrap(),
+            region_data("Beta")
+        );
+    }
+}
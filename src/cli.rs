@@ -0,0 +1,147 @@
+//! Command line arguments
+
+use rust_i18n::t;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Command run instead of opening the window
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Run the scheduled backups headless, optionally into another folder
+    Daemon { destination: Option<PathBuf> },
+    /// Delete exports by the retention rules, or only list them in a dry run
+    Prune {
+        destination: Option<PathBuf>,
+        dry_run: bool,
+    },
+}
+
+/// Arguments passed on the command line
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// Additional save roots given with `--saves-dir`, may be repeated
+    pub saves_dirs: Vec<PathBuf>,
+    pub command: Option<Command>,
+}
+
+impl CliArgs {
+    /// Parses the arguments, without the program name
+    pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let arg_str = arg.to_string_lossy();
+            if arg_str == "--saves-dir" {
+                let path = args.next().ok_or_else(|| {
+                    t!("errors.missing_argument_value", flag = "--saves-dir").to_string()
+                })?;
+                parsed.saves_dirs.push(PathBuf::from(path));
+            } else if let Some(path) = arg_str.strip_prefix("--saves-dir=") {
+                parsed.saves_dirs.push(PathBuf::from(path));
+            } else if arg_str == "daemon" && parsed.command.is_none() {
+                parsed.command = Some(Command::Daemon { destination: None });
+            } else if arg_str == "prune" && parsed.command.is_none() {
+                parsed.command = Some(Command::Prune {
+                    destination: None,
+                    dry_run: false,
+                });
+            } else if let Some(
+                Command::Daemon { destination } | Command::Prune { destination, .. },
+            ) = &mut parsed.command
+            {
+                if arg_str == "--destination" {
+                    let path = args.next().ok_or_else(|| {
+                        t!("errors.missing_argument_value", flag = "--destination").to_string()
+                    })?;
+                    *destination = Some(PathBuf::from(path));
+                } else if let Some(path) = arg_str.strip_prefix("--destination=") {
+                    *destination = Some(PathBuf::from(path));
+                } else if let (Some(Command::Prune { dry_run, .. }), "--dry-run") =
+                    (&mut parsed.command, arg_str.as_ref())
+                {
+                    *dry_run = true;
+                } else {
+                    return Err(t!("errors.unknown_argument", argument = arg_str).to_string());
+                }
+            } else {
+                return Err(t!("errors.unknown_argument", argument = arg_str).to_string());
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn test_parse_saves_dirs() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+        assert_eq!(
+            parse(&["--saves-dir", "/srv/saves", "--saves-dir=/tmp/worlds"])
+                .unwrap()
+                .saves_dirs,
+            vec![PathBuf::from("/srv/saves"), PathBuf::from("/tmp/worlds")]
+        );
+    }
+
+    #[test]
+    fn test_parse_daemon() {
+        assert_eq!(
+            parse(&["daemon"]).unwrap().command,
+            Some(Command::Daemon { destination: None })
+        );
+        let args = parse(&[
+            "daemon",
+            "--saves-dir",
+            "/srv/saves",
+            "--destination=/backups",
+        ])
+        .unwrap();
+        assert_eq!(args.saves_dirs, vec![PathBuf::from("/srv/saves")]);
+        assert_eq!(
+            args.command,
+            Some(Command::Daemon {
+                destination: Some(PathBuf::from("/backups"))
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_prune() {
+        assert_eq!(
+            parse(&["prune"]).unwrap().command,
+            Some(Command::Prune {
+                destination: None,
+                dry_run: false
+            })
+        );
+        assert_eq!(
+            parse(&["prune", "--dry-run", "--destination", "/backups"])
+                .unwrap()
+                .command,
+            Some(Command::Prune {
+                destination: Some(PathBuf::from("/backups")),
+                dry_run: true
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--saves-dir"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["--destination", "/backups"]).is_err());
+        assert!(parse(&["daemon", "--destination"]).is_err());
+        assert!(parse(&["daemon", "daemon"]).is_err());
+        assert!(parse(&["daemon", "--dry-run"]).is_err());
+        assert!(parse(&["prune", "daemon"]).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Consistency guard for exports
+//!
+//! Exporting while Hytale is running can capture region files in the middle of
+//! a write. This module detects a running game client or server and lock files
+//! in the world folder, and tracks file sizes and modification times so files
+//! that changed while they were being archived can be flagged.
+
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use walkdir::WalkDir;
+
+/// How deep to look for lock files below the world folder
+const LOCK_FILE_SEARCH_DEPTH: usize = 2;
+
+/// Signs that a world may currently be written to
+#[derive(Clone, Default)]
+pub struct WorldUsage {
+    /// Names of running Hytale client or server processes
+    pub processes: Vec<String>,
+    /// Lock files found in the world folder
+    pub lock_files: Vec<PathBuf>,
+}
+
+impl WorldUsage {
+    pub fn is_in_use(&self) -> bool {
+        !self.processes.is_empty() || !self.lock_files.is_empty()
+    }
+}
+
+/// Size and modification time of a file at one point in time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FileState {
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileState {
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Option<Self> {
+        fs::metadata(path)
+            .ok()
+            .map(|metadata| Self::from_metadata(&metadata))
+    }
+}
+
+/// Checks whether a process looks like the Hytale client or a Hytale server
+///
+/// The launcher and this exporter are ignored since they do not write worlds.
+/// Dedicated servers run inside a Java process, so the command line is checked too.
+pub fn is_game_process(name: &str, cmd: &[String]) -> bool {
+    let name = name.to_lowercase();
+    if name.contains("exporter") || name.contains("launcher") {
+        return false;
+    }
+
+    name.contains("hytale")
+        || cmd
+            .iter()
+            .any(|arg| arg.to_lowercase().contains("hytaleserver"))
+}
+
+/// Returns the names of all running Hytale client and server processes
+pub fn running_game_processes() -> Vec<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+
+    let own_pid = sysinfo::get_current_pid().ok();
+    let mut names: Vec<String> = system
+        .processes()
+        .values()
+        .filter(|process| Some(process.pid()) != own_pid)
+        .filter(|process| {
+            let cmd: Vec<String> = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect();
+            is_game_process(&process.name().to_string_lossy(), &cmd)
+        })
+        .map(|process| process.name().to_string_lossy().to_string())
+        .collect();
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Finds lock files in the top levels of a world folder
+pub fn find_lock_files(world_path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(world_path)
+        .max_depth(LOCK_FILE_SEARCH_DEPTH)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            name == "lock" || name.ends_with(".lock")
+        })
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Checks whether the game or a server might currently be writing the world
+pub fn detect_world_usage(world_path: &Path) -> WorldUsage {
+    detect_usage_of_worlds(&[world_path])
+}
+
+/// Checks several worlds at once, listing the running processes only once
+pub fn detect_usage_of_worlds(world_paths: &[&Path]) -> WorldUsage {
+    WorldUsage {
+        processes: running_game_processes(),
+        lock_files: world_paths
+            .iter()
+            .flat_map(|path| find_lock_files(path))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_process_detection() {
+        assert!(is_game_process("HytaleClient", &[]));
+        assert!(is_game_process("HytaleClient.exe", &[]));
+        assert!(is_game_process(
+            "java",
+            &[
+                "java".to_string(),
+                "-jar".to_string(),
+                "HytaleServer.jar".to_string()
+            ]
+        ));
+        assert!(!is_game_process("hytale-world-exporter", &[]));
+        assert!(!is_game_process("HytaleLauncher", &[]));
+        assert!(!is_game_process(
+            "java",
+            &["-jar".to_string(), "other.jar".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_find_lock_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("universe")).unwrap();
+        fs::write(dir.path().join("session.lock"), b"").unwrap();
+        fs::write(dir.path().join("universe/lock"), b"").unwrap();
+        fs::write(dir.path().join("config.json"), b"{}").unwrap();
+
+        let mut locks = find_lock_files(dir.path());
+        locks.sort();
+        assert_eq!(
+            locks,
+            vec![
+                dir.path().join("session.lock"),
+                dir.path().join("universe/lock")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_usage_of_worlds() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second) = (dir.path().join("First"), dir.path().join("Second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("session.lock"), b"").unwrap();
+        fs::write(second.join("session.lock"), b"").unwrap();
+
+        let usage = detect_usage_of_worlds(&[&first, &second]);
+        assert_eq!(
+            usage.lock_files,
+            vec![first.join("session.lock"), second.join("session.lock")]
+        );
+    }
+
+    #[test]
+    fn test_file_state_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("0.0.region");
+        fs::write(&path, b"before").unwrap();
+        let before = FileState::read(&path).unwrap();
+
+        fs::write(&path, b"after the write").unwrap();
+        assert_ne!(FileState::read(&path), Some(before));
+    }
+}
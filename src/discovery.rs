@@ -0,0 +1,211 @@
+//! Discovery of Hytale save folders on Linux
+//!
+//! Besides the Flatpak launcher, Hytale can run from a native install or from a
+//! Wine or Proton prefix. This module looks for `Hytale/UserData/Saves` in the
+//! usual locations of each so detected folders can be offered as save roots.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::settings::SaveRoot;
+
+/// Path of the saves folder relative to the Hytale data folder
+const SAVES_SUBPATH: &str = "Hytale/UserData/Saves";
+
+/// Locations to search, taken from the environment
+#[derive(Clone, Debug, Default)]
+pub struct SearchLocations {
+    pub home: PathBuf,
+    /// `$XDG_DATA_HOME`, defaults to `~/.local/share`
+    pub xdg_data_home: Option<PathBuf>,
+    /// `$XDG_DATA_DIRS`
+    pub xdg_data_dirs: Vec<PathBuf>,
+    /// `$WINEPREFIX`
+    pub wine_prefix: Option<PathBuf>,
+}
+
+impl SearchLocations {
+    /// Reads the search locations from the current environment
+    pub fn from_env() -> Option<Self> {
+        let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+        Some(Self {
+            home: dirs::home_dir()?,
+            xdg_data_home: non_empty("XDG_DATA_HOME").map(PathBuf::from),
+            xdg_data_dirs: non_empty("XDG_DATA_DIRS")
+                .map(|value| std::env::split_paths(&value).collect())
+                .unwrap_or_default(),
+            wine_prefix: non_empty("WINEPREFIX").map(PathBuf::from),
+        })
+    }
+
+    fn data_home(&self) -> PathBuf {
+        self.xdg_data_home
+            .clone()
+            .unwrap_or_else(|| self.home.join(".local/share"))
+    }
+}
+
+/// Lists the subfolders of a folder, ignoring errors
+fn subdirectories(path: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Finds the saves folders of all Windows users inside a Wine prefix
+fn saves_in_prefix(prefix: &Path) -> Vec<PathBuf> {
+    subdirectories(&prefix.join("drive_c/users"))
+        .into_iter()
+        .filter(|user| !user.ends_with("Public"))
+        .flat_map(|user| {
+            [
+                user.join("AppData/Roaming").join(SAVES_SUBPATH),
+                user.join("Application Data").join(SAVES_SUBPATH),
+            ]
+        })
+        .collect()
+}
+
+/// Lists all places a saves folder might be, labelled by installation type
+pub fn candidate_save_roots(locations: &SearchLocations) -> Vec<SaveRoot> {
+    let home = &locations.home;
+    let mut candidates = Vec::new();
+    let mut add = |name: String, path: PathBuf| candidates.push(SaveRoot { name, path });
+
+    add(
+        "Flatpak".to_string(),
+        home.join(".var/app/com.hypixel.HytaleLauncher/data")
+            .join(SAVES_SUBPATH),
+    );
+
+    add(
+        "Native".to_string(),
+        locations.data_home().join(SAVES_SUBPATH),
+    );
+    for dir in &locations.xdg_data_dirs {
+        add("Native".to_string(), dir.join(SAVES_SUBPATH));
+    }
+
+    // Wine prefixes: the explicit one, the default and the ones managed by Lutris and Bottles
+    let mut prefixes: Vec<PathBuf> = locations.wine_prefix.iter().cloned().collect();
+    prefixes.push(home.join(".wine"));
+    prefixes.extend(subdirectories(&home.join("Games")));
+    prefixes.extend(subdirectories(
+        &locations.data_home().join("bottles/bottles"),
+    ));
+    prefixes.extend(subdirectories(
+        &home.join(".var/app/com.usebottles.bottles/data/bottles/bottles"),
+    ));
+    for prefix in prefixes {
+        let label = format!(
+            "Wine ({})",
+            prefix.file_name().unwrap_or_default().to_string_lossy()
+        );
+        for path in saves_in_prefix(&prefix) {
+            add(label.clone(), path);
+        }
+    }
+
+    // Proton keeps one prefix per game below each Steam library's compatdata folder
+    let steam_roots = [
+        home.join(".steam/steam"),
+        locations.data_home().join("Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ];
+    for steam in &steam_roots {
+        for compat in subdirectories(&steam.join("steamapps/compatdata")) {
+            let label = format!(
+                "Proton ({})",
+                compat.file_name().unwrap_or_default().to_string_lossy()
+            );
+            for path in saves_in_prefix(&compat.join("pfx")) {
+                add(label.clone(), path);
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Finds existing saves folders, with duplicates (e.g. through symlinks) removed
+pub fn discover_in(locations: &SearchLocations) -> Vec<SaveRoot> {
+    let mut found: Vec<(PathBuf, SaveRoot)> = Vec::new();
+    for candidate in candidate_save_roots(locations) {
+        let Ok(canonical) = fs::canonicalize(&candidate.path) else {
+            continue;
+        };
+        if canonical.is_dir() && !found.iter().any(|(path, _)| *path == canonical) {
+            found.push((canonical, candidate));
+        }
+    }
+    found.into_iter().map(|(_, root)| root).collect()
+}
+
+/// Finds the saves folders of all Hytale installations on this system
+pub fn discover_save_roots() -> Vec<SaveRoot> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    SearchLocations::from_env()
+        .map(|locations| discover_in(&locations))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_installations() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().to_path_buf();
+        let saves = [
+            home.join(".var/app/com.hypixel.HytaleLauncher/data/Hytale/UserData/Saves"),
+            home.join(".local/share/Hytale/UserData/Saves"),
+            home.join(".wine/drive_c/users/me/AppData/Roaming/Hytale/UserData/Saves"),
+            home.join(
+                ".steam/steam/steamapps/compatdata/1234/pfx/drive_c/users/steamuser/AppData/Roaming/Hytale/UserData/Saves",
+            ),
+        ];
+        for path in &saves {
+            fs::create_dir_all(path).unwrap();
+        }
+        // An unrelated prefix without Hytale
+        fs::create_dir_all(home.join("Games/other/drive_c/users/me")).unwrap();
+
+        let found = discover_in(&SearchLocations {
+            home: home.clone(),
+            ..Default::default()
+        });
+        let names: Vec<&str> = found.iter().map(|root| root.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Flatpak", "Native", "Wine (.wine)", "Proton (1234)"]
+        );
+        assert_eq!(found[2].path, saves[2]);
+    }
+
+    #[test]
+    fn test_duplicate_locations_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().to_path_buf();
+        fs::create_dir_all(home.join(".local/share/Hytale/UserData/Saves")).unwrap();
+
+        // $XDG_DATA_HOME and $XDG_DATA_DIRS pointing at the same folder
+        let found = discover_in(&SearchLocations {
+            home: home.clone(),
+            xdg_data_home: Some(home.join(".local/share")),
+            xdg_data_dirs: vec![home.join(".local/share")],
+            wine_prefix: None,
+        });
+        assert_eq!(found.len(), 1);
+    }
+}
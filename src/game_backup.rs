@@ -0,0 +1,406 @@
+//! Restoring worlds from the game's own backups
+//!
+//! The game keeps ZIP backups of a world in its `backup` folder, with the
+//! world's files either at the top of the archive or in a folder named after
+//! the world. A restore goes through the same staging folder as an import, so
+//! a damaged backup leaves the world untouched. The world's `backup` and `logs`
+//! folders are its history rather than its state and are always kept.
+//!
+//! The backups are cleaned up by hand; [`backups_older_than`] and
+//! [`backups_beyond_newest`] pick which of them a bulk deletion selects.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use rust_i18n::t;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+use crate::backup::{replace_world, stage_archive};
+use crate::manifest::is_metadata_entry;
+use crate::models::{ArchiveDetails, BackupFormat, BackupInfo, GameBackupInfo, RestorePlan};
+
+/// Folders of a world that a restore keeps as they are
+pub const KEPT_FOLDERS: [&str; 2] = ["backup", "logs"];
+
+impl BackupFormat {
+    pub fn label(self) -> String {
+        match self {
+            BackupFormat::Zip => "ZIP".to_string(),
+            BackupFormat::Gzip => "gzip".to_string(),
+            BackupFormat::Unknown => t!("app.unknown_format").to_string(),
+        }
+    }
+}
+
+/// Recognizes the format of a backup file by its first bytes
+pub fn detect_format(path: &Path) -> BackupFormat {
+    let mut magic = [0u8; 4];
+    let read = File::open(path).and_then(|mut file| file.read_exact(&mut magic));
+    match (read, magic) {
+        // An empty ZIP file starts with its end of central directory
+        (Ok(()), [b'P', b'K', 3, 4] | [b'P', b'K', 5, 6]) => BackupFormat::Zip,
+        (Ok(()), [0x1f, 0x8b, _, _]) => BackupFormat::Gzip,
+        _ => BackupFormat::Unknown,
+    }
+}
+
+/// Format of a backup file and the number of files in it, if it is a readable ZIP
+pub fn read_archive_details(path: &Path) -> ArchiveDetails {
+    let format = detect_format(path);
+    let entry_count = (format == BackupFormat::Zip)
+        .then(|| File::open(path).ok())
+        .flatten()
+        .and_then(|file| ZipArchive::new(file).ok())
+        .map(|archive| {
+            archive
+                .file_names()
+                .filter(|name| !name.ends_with('/') && !is_metadata_entry(name))
+                .count()
+        });
+    ArchiveDetails {
+        format,
+        entry_count,
+    }
+}
+
+/// Parses the time in a backup's file name, such as `2026-01-13_19-35-06.zip`
+///
+/// A date alone counts as midnight. Text before the date is skipped.
+pub fn parse_name_time(name: &str) -> Option<NaiveDateTime> {
+    let starts = name
+        .char_indices()
+        .filter(|&(index, c)| {
+            c.is_ascii_digit() && !name[..index].ends_with(|c: char| c.is_ascii_digit())
+        })
+        .map(|(index, _)| index);
+    for start in starts {
+        if let Some(time) = name.get(start..start + 19).and_then(|text| {
+            [
+                "%Y-%m-%d_%H-%M-%S",
+                "%Y-%m-%d %H-%M-%S",
+                "%Y-%m-%dT%H-%M-%S",
+            ]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        }) {
+            return Some(time);
+        }
+        if let Some(date) = name
+            .get(start..start + 10)
+            .and_then(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok())
+        {
+            return date.and_hms_opt(0, 0, 0);
+        }
+    }
+    None
+}
+
+/// In-game backups made before the cutoff
+///
+/// Backups whose time cannot be read are never selected.
+pub fn backups_older_than(backups: &[BackupInfo], cutoff: DateTime<Local>) -> Vec<PathBuf> {
+    backups
+        .iter()
+        .filter(|backup| backup.time().is_some_and(|time| time < cutoff))
+        .map(|backup| backup.path.clone())
+        .collect()
+}
+
+/// In-game backups other than the newest `keep` ones
+///
+/// Backups whose time cannot be read are never selected, as they may well be
+/// newer than the ones kept.
+pub fn backups_beyond_newest(backups: &[BackupInfo], keep: usize) -> Vec<PathBuf> {
+    let mut dated: Vec<(DateTime<Local>, &BackupInfo)> = backups
+        .iter()
+        .filter_map(|backup| Some((backup.time()?, backup)))
+        .collect();
+    dated.sort_by(|(a_time, a), (b_time, b)| (b_time, &b.name).cmp(&(a_time, &a.name)));
+    dated
+        .into_iter()
+        .skip(keep)
+        .map(|(_, backup)| backup.path.clone())
+        .collect()
+}
+
+/// Reads what an in-game backup of a world contains
+pub fn inspect_game_backup(path: &Path, world_name: &str) -> Result<GameBackupInfo, String> {
+    let format = detect_format(path);
+    if format != BackupFormat::Zip {
+        return Err(t!("errors.unsupported_backup_format", format = format.label()).to_string());
+    }
+
+    let file = File::open(path)
+        .map_err(|e| t!("errors.open_zip_failed", error = e.to_string()).to_string())?;
+    let archive = ZipArchive::new(file)
+        .map_err(|e| t!("errors.read_zip_failed", error = e.to_string()).to_string())?;
+    let names: Vec<&str> = archive
+        .file_names()
+        .filter(|name| !is_metadata_entry(name))
+        .collect();
+
+    let prefix = format!("{}/", world_name);
+    let root = (!names.is_empty() && names.iter().all(|name| name.starts_with(&prefix)))
+        .then(|| world_name.to_string());
+    let top_level: BTreeSet<String> = names
+        .iter()
+        .map(|name| match &root {
+            Some(_) => &name[prefix.len()..],
+            None => name,
+        })
+        .filter_map(|name| name.split('/').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(GameBackupInfo {
+        entry_count: names.iter().filter(|name| !name.ends_with('/')).count(),
+        root,
+        top_level: top_level.into_iter().collect(),
+    })
+}
+
+/// Compares the top level of a world with what a backup restores
+pub fn restore_plan(world_path: &Path, info: &GameBackupInfo) -> RestorePlan {
+    let current: BTreeSet<String> = fs::read_dir(world_path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let restored: BTreeSet<String> = info
+        .top_level
+        .iter()
+        .filter(|name| !KEPT_FOLDERS.contains(&name.as_str()))
+        .cloned()
+        .collect();
+
+    let mut plan = RestorePlan::default();
+    for name in &current {
+        if KEPT_FOLDERS.contains(&name.as_str()) {
+            plan.kept.push(name.clone());
+        } else if restored.contains(name) {
+            plan.replaced.push(name.clone());
+        } else {
+            plan.removed.push(name.clone());
+        }
+    }
+    plan.added = restored.difference(&current).cloned().collect();
+    plan
+}
+
+/// Restores a world from an in-game backup, replacing it if it exists
+pub fn restore_game_backup(
+    backup_path: &Path,
+    world_path: &Path,
+    info: &GameBackupInfo,
+) -> Result<(), String> {
+    let (staging_path, _) = stage_archive(backup_path, world_path, None)?;
+    let content = match &info.root {
+        Some(root) => staging_path.join(root),
+        None => staging_path.clone(),
+    };
+    // The world is only deleted once the restored copy is in place
+    let result = replace_world(&content, world_path, &KEPT_FOLDERS);
+    if staging_path.exists() {
+        let _ = fs::remove_dir_all(&staging_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_zip(path: &Path, files: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, contents) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = dir.path().join("a.zip");
+        write_zip(&zip, &[("config.json", "{}")]);
+        let empty = dir.path().join("empty.zip");
+        write_zip(&empty, &[]);
+        fs::write(dir.path().join("a.tar.gz"), [0x1f, 0x8b, 8, 0, 0]).unwrap();
+        fs::write(dir.path().join("a.txt"), "text").unwrap();
+
+        assert_eq!(detect_format(&zip), BackupFormat::Zip);
+        assert_eq!(detect_format(&empty), BackupFormat::Zip);
+        assert_eq!(
+            detect_format(&dir.path().join("a.tar.gz")),
+            BackupFormat::Gzip
+        );
+        assert_eq!(
+            detect_format(&dir.path().join("a.txt")),
+            BackupFormat::Unknown
+        );
+        assert!(inspect_game_backup(&dir.path().join("a.txt"), "World").is_err());
+    }
+
+    #[test]
+    fn test_parse_name_time() {
+        let time = |text: &str| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok();
+        assert_eq!(
+            parse_name_time("2026-01-13_19-35-06.zip"),
+            time("2026-01-13 19:35:06")
+        );
+        assert_eq!(
+            parse_name_time("backup 2026-01-13 19-35-06.zip"),
+            time("2026-01-13 19:35:06")
+        );
+        assert_eq!(
+            parse_name_time("World_2026-01-12.zip"),
+            time("2026-01-12 00:00:00")
+        );
+        assert_eq!(parse_name_time("v2-2026-13-40.zip"), None);
+        assert_eq!(parse_name_time("latest.zip"), None);
+    }
+
+    #[test]
+    fn test_read_archive_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = dir.path().join("a.zip");
+        write_zip(&zip, &[("universe/region.bin", ""), ("config.json", "{}")]);
+        fs::write(dir.path().join("broken.zip"), b"PK\x03\x04broken").unwrap();
+
+        assert_eq!(
+            read_archive_details(&zip),
+            ArchiveDetails {
+                format: BackupFormat::Zip,
+                entry_count: Some(2),
+            }
+        );
+        assert_eq!(
+            read_archive_details(&dir.path().join("broken.zip")),
+            ArchiveDetails {
+                format: BackupFormat::Zip,
+                entry_count: None,
+            }
+        );
+    }
+
+    fn backup(name: &str, age_days: Option<u64>) -> BackupInfo {
+        BackupInfo {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            size: 1,
+            created: None,
+            modified: age_days.map(|days| {
+                std::time::SystemTime::UNIX_EPOCH
+                    + std::time::Duration::from_secs((100 - days) * 86_400)
+            }),
+            name_time: None,
+        }
+    }
+
+    #[test]
+    fn test_select_in_game_backups() {
+        let backups = vec![
+            backup("b.zip", Some(1)),
+            backup("d.zip", Some(40)),
+            backup("a.zip", Some(3)),
+            backup("unknown.zip", None),
+            backup("c.zip", Some(40)),
+        ];
+        let cutoff = DateTime::from(
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(70 * 86_400),
+        );
+        assert_eq!(
+            backups_older_than(&backups, cutoff),
+            vec![PathBuf::from("d.zip"), PathBuf::from("c.zip")]
+        );
+        // Backups of unknown time are never selected, like by age
+        assert_eq!(
+            backups_beyond_newest(&backups, 2),
+            vec![PathBuf::from("d.zip"), PathBuf::from("c.zip")]
+        );
+        assert_eq!(
+            backups_beyond_newest(&backups, 3),
+            vec![PathBuf::from("c.zip")]
+        );
+        assert!(backups_beyond_newest(&backups, 4).is_empty());
+    }
+
+    #[test]
+    fn test_restore_in_place_and_as_new_world() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(world.join("universe")).unwrap();
+        fs::create_dir_all(world.join("backup")).unwrap();
+        fs::write(world.join("universe/region.bin"), "new").unwrap();
+        fs::write(world.join("extra.txt"), "extra").unwrap();
+        let backup = world.join("backup/2026-01-12_10-00-00.zip");
+        write_zip(
+            &backup,
+            &[
+                ("World/universe/region.bin", "old"),
+                ("World/config.json", "{}"),
+            ],
+        );
+
+        let info = inspect_game_backup(&backup, "World").unwrap();
+        assert_eq!(
+            info,
+            GameBackupInfo {
+                entry_count: 2,
+                root: Some("World".to_string()),
+                top_level: vec!["config.json".to_string(), "universe".to_string()],
+            }
+        );
+        assert_eq!(
+            restore_plan(&world, &info),
+            RestorePlan {
+                replaced: vec!["universe".to_string()],
+                removed: vec!["extra.txt".to_string()],
+                added: vec!["config.json".to_string()],
+                kept: vec!["backup".to_string()],
+            }
+        );
+
+        let copy = dir.path().join("World (restored)");
+        restore_game_backup(&backup, &copy, &info).unwrap();
+        assert_eq!(
+            fs::read_to_string(copy.join("universe/region.bin")).unwrap(),
+            "old"
+        );
+        assert!(!copy.join("backup").exists());
+
+        restore_game_backup(&backup, &world, &info).unwrap();
+        assert_eq!(
+            fs::read_to_string(world.join("universe/region.bin")).unwrap(),
+            "old"
+        );
+        assert!(world.join("config.json").exists());
+        assert!(!world.join("extra.txt").exists());
+        assert!(backup.exists());
+        // Only the two worlds are left, no staging folders
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_backup_without_root_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup = dir.path().join("backup.zip");
+        write_zip(
+            &backup,
+            &[("universe/region.bin", "old"), ("logs/1.log", "")],
+        );
+        let info = inspect_game_backup(&backup, "World").unwrap();
+        assert_eq!(info.root, None);
+        assert_eq!(info.top_level, vec!["logs", "universe"]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Persistent history of finished jobs
+//!
+//! Every export, import and verification is recorded with its outcome in
+//! `history.json` in the application's config directory. Only the most recent
+//! entries are kept. The app and the daemon both add to it, so new entries are
+//! added to the file as it is on disk rather than to a copy in memory.
+
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use crate::jobs::FinishedJob;
+use crate::models::JobKind;
+use crate::utils::{config_file, write_private_file};
+
+const HISTORY_FILE: &str = "history.json";
+
+/// Number of entries kept in the history
+pub const MAX_HISTORY_ENTRIES: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    Succeeded,
+    /// Finished with problems, such as files that changed during an export
+    Warning(String),
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Start time as `2026-01-13 19:35:06`
+    pub time: String,
+    pub kind: JobKind,
+    pub world: String,
+    pub destination: Option<PathBuf>,
+    pub size: Option<u64>,
+    pub duration_secs: f64,
+    pub outcome: Outcome,
+}
+
+impl HistoryEntry {
+    pub fn from_job(job: &FinishedJob) -> Self {
+        let (destination, size, outcome) = match &job.result {
+            Ok(report) => (
+                Some(report.destination.clone()),
+                report.size,
+                match &report.warning {
+                    Some(warning) => Outcome::Warning(warning.clone()),
+                    None => Outcome::Succeeded,
+                },
+            ),
+            Err(e) => (None, None, Outcome::Failed(e.clone())),
+        };
+        Self {
+            time: job.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            kind: job.kind,
+            world: job.world.clone(),
+            destination,
+            size,
+            duration_secs: job.duration.as_secs_f64(),
+            outcome,
+        }
+    }
+}
+
+/// Loads the history, oldest entry first
+pub fn load_history() -> Vec<HistoryEntry> {
+    config_file(HISTORY_FILE)
+        .map(|path| read_history(&path))
+        .unwrap_or_default()
+}
+
+fn read_history(path: &Path) -> Vec<HistoryEntry> {
+    fs::read(path)
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Adds entries to the stored history and returns all of it
+///
+/// Entries another process added since the history was loaded are kept.
+pub fn append_history(entries: Vec<HistoryEntry>) -> Result<Vec<HistoryEntry>, String> {
+    update_history(|history| {
+        for entry in entries {
+            add_entry(history, entry);
+        }
+    })
+}
+
+/// Deletes the stored history
+pub fn clear_history() -> Result<(), String> {
+    update_history(Vec::clear).map(|_| ())
+}
+
+fn update_history(
+    change: impl FnOnce(&mut Vec<HistoryEntry>),
+) -> Result<Vec<HistoryEntry>, String> {
+    let path = config_file(HISTORY_FILE)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| t!("errors.create_dir_failed", error = e.to_string()).to_string())?;
+    }
+    update_history_at(&path, change)
+}
+
+fn update_history_at(
+    path: &Path,
+    change: impl FnOnce(&mut Vec<HistoryEntry>),
+) -> Result<Vec<HistoryEntry>, String> {
+    let write_error =
+        |e: std::io::Error| t!("errors.write_file_failed", error = e.to_string()).to_string();
+
+    // Read and write under a lock, so two processes cannot drop each other's changes
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))
+        .map_err(write_error)?;
+    lock.lock().map_err(write_error)?;
+
+    let mut history = read_history(path);
+    change(&mut history);
+    let json = serde_json::to_vec_pretty(&history)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+    write_private_file(path, &json).map_err(write_error)?;
+    Ok(history)
+}
+
+/// Appends an entry, dropping the oldest ones beyond the limit
+pub fn add_entry(history: &mut Vec<HistoryEntry>, entry: HistoryEntry) {
+    history.push(entry);
+    if history.len() > MAX_HISTORY_ENTRIES {
+        history.drain(..history.len() - MAX_HISTORY_ENTRIES);
+    }
+}
+
+/// Formats a duration as `0.4 s`, `42 s` or `3 min 5 s`
+pub fn format_duration(secs: f64) -> String {
+    if secs < 10.0 {
+        format!("{:.1} s", secs)
+    } else if secs < 60.0 {
+        format!("{:.0} s", secs)
+    } else {
+        let secs = secs.round() as u64;
+        format!("{} min {} s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::JobReport;
+    use chrono::{Local, TimeZone};
+    use std::time::Duration;
+
+    fn finished(world: &str, result: Result<JobReport, String>) -> FinishedJob {
+        FinishedJob {
+            kind: JobKind::Export,
+            world: world.to_string(),
+            started_at: Local.with_ymd_and_hms(2026, 1, 13, 19, 35, 6).unwrap(),
+            duration: Duration::from_millis(1500),
+            result,
+        }
+    }
+
+    #[test]
+    fn test_entry_from_job() {
+        let entry = HistoryEntry::from_job(&finished(
+            "World",
+            Ok(JobReport {
+                destination: PathBuf::from("/exports/World.zip"),
+                size: Some(1024),
+                message: String::new(),
+                warning: Some("1 file changed".to_string()),
+            }),
+        ));
+        assert_eq!(entry.time, "2026-01-13 19:35:06");
+        assert_eq!(entry.size, Some(1024));
+        assert_eq!(entry.duration_secs, 1.5);
+        assert_eq!(
+            entry.outcome,
+            Outcome::Warning("1 file changed".to_string())
+        );
+
+        let entry = HistoryEntry::from_job(&finished("World", Err("disk full".to_string())));
+        assert_eq!(entry.destination, None);
+        assert_eq!(entry.outcome, Outcome::Failed("disk full".to_string()));
+
+        // Entries survive a round trip through the history file format
+        let json = serde_json::to_vec(std::slice::from_ref(&entry)).unwrap();
+        let loaded: Vec<HistoryEntry> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(loaded, vec![entry]);
+    }
+
+    #[test]
+    fn test_history_is_trimmed() {
+        let mut history = Vec::new();
+        for index in 0..MAX_HISTORY_ENTRIES + 10 {
+            add_entry(
+                &mut history,
+                HistoryEntry::from_job(&finished(&index.to_string(), Err(String::new()))),
+            );
+        }
+        assert_eq!(history.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history[0].world, "10");
+    }
+
+    #[test]
+    fn test_append_keeps_entries_of_other_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILE);
+        let append = |world: &str| {
+            let entry = HistoryEntry::from_job(&finished(world, Err(String::new())));
+            update_history_at(&path, |history| add_entry(history, entry)).unwrap()
+        };
+
+        // The app loaded the history before the daemon added to it
+        let app_history = append("app");
+        append("daemon");
+        assert_eq!(app_history.len(), 1);
+
+        let history = append("app again");
+        let worlds: Vec<&str> = history.iter().map(|entry| entry.world.as_str()).collect();
+        assert_eq!(worlds, vec!["app", "daemon", "app again"]);
+        assert_eq!(read_history(&path), history);
+
+        assert!(update_history_at(&path, Vec::clear).unwrap().is_empty());
+        assert_eq!(append("after clear").len(), 1);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0.42), "0.4 s");
+        assert_eq!(format_duration(42.4), "42 s");
+        assert_eq!(format_duration(185.0), "3 min 5 s");
+    }
+}
@@ -0,0 +1,491 @@
+//! Queue for exports, imports and verifications
+//!
+//! Jobs run on background threads, at most a configured number at a time and
+//! otherwise in the order they were added. Jobs on the same world never run at
+//! the same time, so an import cannot replace a world that is being exported.
+//! Each job reports its progress
+//! separately; finished jobs are handed back to the app, which shows their
+//! result and records them in the history.
+
+use chrono::{DateTime, Local};
+use eframe::egui;
+use rust_i18n::t;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::models::{BackupProgress, ExportReport, JobKind, VerifyOutcome};
+use crate::volumes::archive_size;
+
+/// What a successful job produced
+#[derive(Clone, Debug)]
+pub struct JobReport {
+    /// Archive or world folder the job wrote or checked
+    pub destination: PathBuf,
+    pub size: Option<u64>,
+    /// Status message shown when the job finishes
+    pub message: String,
+    /// The job finished but found problems, such as a failed verification
+    pub warning: Option<String>,
+}
+
+impl JobReport {
+    /// Result of a finished export, with its summary as the message
+    pub fn from_export(report: &ExportReport) -> Self {
+        let mut message = format!(
+            "{}\n{}",
+            t!("app.backup_success"),
+            report.path.to_string_lossy()
+        );
+        let mut warning = None;
+        if let Some(signer) = &report.signed_by {
+            message.push_str(&format!("\n{}", t!("app.signed_as", name = signer)));
+        }
+        if report.volume_count > 1 {
+            message.push_str(&format!(
+                "\n{}",
+                t!("app.volumes_written", count = report.volume_count)
+            ));
+        }
+        match &report.verification {
+            Some(VerifyOutcome::Verified(count)) => {
+                message.push_str(&format!("\n{}", t!("app.verify_success", count = count)));
+            }
+            Some(VerifyOutcome::Failed(e)) => {
+                let failed = format!("{} {}", t!("app.verify_failed"), e);
+                message.push_str(&format!("\n{}", failed));
+                warning = Some(failed);
+            }
+            None => {}
+        }
+        if !report.changed_files.is_empty() {
+            let changed = t!(
+                "app.files_changed_during_export",
+                count = report.changed_files.len(),
+                files = summarize_files(&report.changed_files)
+            )
+            .to_string();
+            message.push_str(&format!("\n{}", changed));
+            warning.get_or_insert(changed);
+        }
+
+        Self {
+            destination: report.path.clone(),
+            size: Some(archive_size(&report.path)),
+            message,
+            warning,
+        }
+    }
+
+    /// Adds a warning, after any the report already has
+    pub fn add_warning(&mut self, warning: String) {
+        self.warning = Some(match self.warning.take() {
+            Some(existing) => format!("{}\n{}", existing, warning),
+            None => warning,
+        });
+    }
+}
+
+type JobWork = Box<
+    dyn FnOnce(&Arc<Mutex<BackupProgress>>, &egui::Context) -> Result<JobReport, String> + Send,
+>;
+
+struct Job {
+    id: u64,
+    kind: JobKind,
+    world: String,
+    /// World folders the job reads or writes
+    world_paths: Vec<PathBuf>,
+    progress: Arc<Mutex<BackupProgress>>,
+    work: Option<JobWork>,
+}
+
+impl Job {
+    fn shares_world_with(&self, other: &Job) -> bool {
+        self.world_paths.iter().any(|path| {
+            other
+                .world_paths
+                .iter()
+                .any(|other| path.starts_with(other) || other.starts_with(path))
+        })
+    }
+}
+
+/// A running or queued job, as shown in the toolbar
+#[derive(Clone)]
+pub struct JobStatus {
+    pub id: u64,
+    pub kind: JobKind,
+    pub world: String,
+    pub progress: BackupProgress,
+}
+
+/// A job that has finished, successfully or not
+pub struct FinishedJob {
+    pub kind: JobKind,
+    pub world: String,
+    pub started_at: DateTime<Local>,
+    pub duration: Duration,
+    pub result: Result<JobReport, String>,
+}
+
+struct QueueState {
+    next_id: u64,
+    max_running: usize,
+    queued: VecDeque<Job>,
+    running: Vec<Job>,
+    finished: Vec<FinishedJob>,
+}
+
+impl Default for QueueState {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            max_running: 1,
+            queued: VecDeque::new(),
+            running: Vec::new(),
+            finished: Vec::new(),
+        }
+    }
+}
+
+/// Runs jobs in the background with limited concurrency
+#[derive(Clone, Default)]
+pub struct JobQueue {
+    state: Arc<Mutex<QueueState>>,
+}
+
+impl JobQueue {
+    /// Adds a job, starting it right away if a slot is free
+    ///
+    /// `world_paths` are the world folders the job reads or writes; the job
+    /// waits for running and earlier queued jobs on any of them.
+    pub fn push(
+        &self,
+        kind: JobKind,
+        world: String,
+        world_paths: Vec<PathBuf>,
+        work: impl FnOnce(&Arc<Mutex<BackupProgress>>, &egui::Context) -> Result<JobReport, String>
+            + Send
+            + 'static,
+        ctx: &egui::Context,
+    ) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.next_id += 1;
+            let id = state.next_id;
+            state.queued.push_back(Job {
+                id,
+                kind,
+                world,
+                world_paths,
+                progress: Arc::new(Mutex::new(BackupProgress::default())),
+                work: Some(Box::new(work)),
+            });
+        }
+        start_jobs(&self.state, ctx);
+    }
+
+    /// Sets how many jobs may run at the same time
+    pub fn set_max_running(&self, max_running: usize, ctx: &egui::Context) {
+        let changed = {
+            let mut state = self.state.lock().unwrap();
+            let max_running = max_running.max(1);
+            let changed = state.max_running != max_running;
+            state.max_running = max_running;
+            changed
+        };
+        if changed {
+            start_jobs(&self.state, ctx);
+        }
+    }
+
+    /// Removes a job that has not started yet
+    pub fn cancel(&self, id: u64) {
+        self.state.lock().unwrap().queued.retain(|job| job.id != id);
+    }
+
+    /// Running jobs followed by queued ones
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        let state = self.state.lock().unwrap();
+        state
+            .running
+            .iter()
+            .chain(&state.queued)
+            .map(|job| JobStatus {
+                id: job.id,
+                kind: job.kind,
+                world: job.world.clone(),
+                // A job that panicked may have poisoned its progress
+                progress: job
+                    .progress
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone(),
+            })
+            .collect()
+    }
+
+    /// Number of running jobs; the first that many statuses are running
+    pub fn running_count(&self) -> usize {
+        self.state.lock().unwrap().running.len()
+    }
+
+    /// Takes the jobs that finished since the last call
+    pub fn take_finished(&self) -> Vec<FinishedJob> {
+        std::mem::take(&mut self.state.lock().unwrap().finished)
+    }
+}
+
+/// Starts queued jobs while fewer than the maximum are running
+///
+/// A job whose world is used by a running or an earlier queued job is skipped
+/// for now, so jobs on one world still run in the order they were added.
+fn start_jobs(state: &Arc<Mutex<QueueState>>, ctx: &egui::Context) {
+    let mut guard = state.lock().unwrap();
+    let mut index = 0;
+    while guard.running.len() < guard.max_running && index < guard.queued.len() {
+        let job = &guard.queued[index];
+        let blocked = guard
+            .running
+            .iter()
+            .chain(guard.queued.iter().take(index))
+            .any(|other| job.shares_world_with(other));
+        if blocked {
+            index += 1;
+            continue;
+        }
+
+        let Some(mut job) = guard.queued.remove(index) else {
+            break;
+        };
+        let Some(work) = job.work.take() else {
+            continue;
+        };
+        let id = job.id;
+        let progress = Arc::clone(&job.progress);
+        progress.lock().unwrap().is_running = true;
+        guard.running.push(job);
+
+        let state = Arc::clone(state);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let started_at = Local::now();
+            let start = Instant::now();
+            // A panicking job must still leave the running list, or it would
+            // hold its slot and block every later job
+            let result = panic::catch_unwind(AssertUnwindSafe(|| work(&progress, &ctx)))
+                .unwrap_or_else(|panic| {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    Err(t!("errors.job_panicked", error = message).to_string())
+                });
+
+            {
+                let mut guard = state.lock().unwrap();
+                if let Some(position) = guard.running.iter().position(|job| job.id == id) {
+                    let job = guard.running.remove(position);
+                    guard.finished.push(FinishedJob {
+                        kind: job.kind,
+                        world: job.world,
+                        started_at,
+                        duration: start.elapsed(),
+                        result,
+                    });
+                }
+            }
+            start_jobs(&state, &ctx);
+            ctx.request_repaint();
+        });
+    }
+}
+
+/// Lists the first few file names of a longer list for a status message
+pub fn summarize_files(files: &[String]) -> String {
+    const SHOWN: usize = 5;
+    let mut summary = files
+        .iter()
+        .take(SHOWN)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if files.len() > SHOWN {
+        summary.push_str(", …");
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn wait_for(queue: &JobQueue, count: usize) -> Vec<FinishedJob> {
+        let start = Instant::now();
+        let mut finished = Vec::new();
+        while finished.len() < count {
+            assert!(start.elapsed() < Duration::from_secs(10), "jobs timed out");
+            finished.extend(queue.take_finished());
+            thread::sleep(Duration::from_millis(5));
+        }
+        finished
+    }
+
+    fn report(name: &str) -> JobReport {
+        JobReport {
+            destination: PathBuf::from(name),
+            size: None,
+            message: String::new(),
+            warning: None,
+        }
+    }
+
+    #[test]
+    fn test_add_warning_keeps_earlier_ones() {
+        let mut report = report("a.zip");
+        report.add_warning("verification failed".to_string());
+        report.add_warning("prune failed".to_string());
+        assert_eq!(
+            report.warning.as_deref(),
+            Some("verification failed\nprune failed")
+        );
+    }
+
+    #[test]
+    fn test_jobs_run_one_at_a_time_in_order() {
+        let queue = JobQueue::default();
+        let ctx = egui::Context::default();
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+
+        for name in ["a", "b", "c"] {
+            let running = Arc::clone(&running);
+            let most_running = Arc::clone(&most_running);
+            queue.push(
+                JobKind::Export,
+                name.to_string(),
+                Vec::new(),
+                move |_, _| {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_running.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    if name == "b" {
+                        Err("failed".to_string())
+                    } else {
+                        Ok(report(name))
+                    }
+                },
+                &ctx,
+            );
+        }
+
+        let finished = wait_for(&queue, 3);
+        let worlds: Vec<&str> = finished.iter().map(|job| job.world.as_str()).collect();
+        assert_eq!(worlds, vec!["a", "b", "c"]);
+        assert!(finished[1].result.is_err());
+        assert_eq!(most_running.load(Ordering::SeqCst), 1);
+        assert!(queue.statuses().is_empty());
+    }
+
+    #[test]
+    fn test_limited_concurrency() {
+        let queue = JobQueue::default();
+        let ctx = egui::Context::default();
+        queue.set_max_running(2, &ctx);
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+
+        for index in 0..4 {
+            let running = Arc::clone(&running);
+            let most_running = Arc::clone(&most_running);
+            queue.push(
+                JobKind::Verify,
+                index.to_string(),
+                Vec::new(),
+                move |_, _| {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_running.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(50));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(report("x"))
+                },
+                &ctx,
+            );
+        }
+        assert_eq!(queue.running_count(), 2);
+        assert_eq!(queue.statuses().len(), 4);
+
+        wait_for(&queue, 4);
+        assert_eq!(most_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_jobs_on_one_world_run_one_after_another() {
+        let queue = JobQueue::default();
+        let ctx = egui::Context::default();
+        queue.set_max_running(3, &ctx);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for (name, world) in [
+            ("export", "saves/A"),
+            ("other", "saves/B"),
+            ("import", "saves/A"),
+        ] {
+            let order = Arc::clone(&order);
+            queue.push(
+                JobKind::Export,
+                name.to_string(),
+                vec![PathBuf::from(world)],
+                move |_, _| {
+                    order.lock().unwrap().push(format!("{} started", name));
+                    thread::sleep(Duration::from_millis(30));
+                    order.lock().unwrap().push(format!("{} done", name));
+                    Ok(report(name))
+                },
+                &ctx,
+            );
+        }
+        assert_eq!(queue.running_count(), 2);
+
+        wait_for(&queue, 3);
+        let order = order.lock().unwrap();
+        let position = |event: &str| order.iter().position(|e| e == event).unwrap();
+        assert!(position("export done") < position("import started"));
+        assert!(position("other started") < position("export done"));
+    }
+
+    #[test]
+    fn test_panicking_job_frees_its_slot() {
+        let queue = JobQueue::default();
+        let ctx = egui::Context::default();
+        queue.push(
+            JobKind::Export,
+            "panics".to_string(),
+            Vec::new(),
+            |_, _| panic!("broken archive"),
+            &ctx,
+        );
+        queue.push(
+            JobKind::Export,
+            "next".to_string(),
+            Vec::new(),
+            |_, _| Ok(report("next")),
+            &ctx,
+        );
+
+        let finished = wait_for(&queue, 2);
+        assert!(finished[0]
+            .result
+            .as_ref()
+            .unwrap_err()
+            .contains("broken archive"));
+        assert!(finished[1].result.is_ok());
+    }
+}
@@ -1,7 +1,7 @@
-/// Log filter module for filtering log lines
-///
-/// This module provides functionality to filter out noise from log files,
-/// such as INFO messages and setup/shutdown progress lines.
+//! Log filter module for filtering log lines
+//!
+//! This module provides functionality to filter out noise from log files,
+//! such as INFO messages and setup/shutdown progress lines.
 
 /// Patterns that indicate lines to filter when "errors only" mode is enabled
 const FILTER_PATTERNS: &[&str] = &[
@@ -1,13 +1,35 @@
 mod app;
 mod backup;
+mod batch;
+mod cli;
+mod consistency;
+mod discovery;
+mod game_backup;
+mod history;
+mod jobs;
 mod log_filter;
+mod manifest;
 mod models;
+mod recycle;
+mod retention;
+mod scanner;
+mod scheduler;
+mod server;
+mod session;
+mod settings;
+mod signing;
+mod snapshot;
+mod template;
 mod utils;
+mod volumes;
+mod watcher;
+mod worlds;
 
 use eframe::egui;
 use rust_i18n::t;
 
 use app::HytaleBackupApp;
+use cli::{CliArgs, Command};
 use utils::detect_system_locale;
 
 rust_i18n::i18n!("locales", fallback = "en");
@@ -17,17 +39,39 @@ fn main() -> Result<(), eframe::Error> {
     let locale = detect_system_locale();
     rust_i18n::set_locale(&locale);
 
+    let args = match CliArgs::parse(std::env::args_os().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(command) = args.command {
+        let result = match command {
+            Command::Daemon { destination } => scheduler::run_daemon(&args.saves_dirs, destination),
+            Command::Prune { destination, dry_run } => retention::run_prune(destination, dry_run),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Window position is restored from the config dir; app settings live in settings.json
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
+            .with_app_id("hytale-world-exporter")
             .with_inner_size([820.0, 660.0])
             .with_resizable(false),
+        persistence_path: utils::app_config_dir().map(|dir| dir.join("window_state.ron")),
         ..Default::default()
     };
 
     eframe::run_native(
         &t!("app.title"),
         options,
-        Box::new(|_cc| Ok(Box::new(HytaleBackupApp::new()))),
+        Box::new(|cc| Ok(Box::new(HytaleBackupApp::new(&cc.egui_ctx, args.saves_dirs)))),
     )
 }
-
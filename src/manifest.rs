@@ -0,0 +1,95 @@
+//! Export manifest embedded in every archive
+//!
+//! The manifest lists every file of the export with its size and SHA-256 hash.
+//! It is stored in a metadata folder inside the archive, so it can be checked
+//! (and its signature verified) before an import extracts anything. Exports
+//! with a password keep the manifest encrypted and store only a stub naming the
+//! world in the clear, so the file list and hashes need the password as well.
+
+use serde::{Deserialize, Serialize};
+
+/// Folder inside the archive holding export metadata; skipped on import
+pub const METADATA_DIR: &str = ".hytale-export/";
+/// Path of the manifest inside the archive
+pub const MANIFEST_PATH: &str = ".hytale-export/manifest.json";
+/// Path of the password-protected ZIP holding the full manifest of an encrypted export
+pub const ENCRYPTED_MANIFEST_PATH: &str = ".hytale-export/manifest.zip";
+/// Path of the detached manifest signature inside the archive
+pub const SIGNATURE_PATH: &str = ".hytale-export/signature.json";
+/// Folder inside the archive holding a dedicated server's config files
+pub const SERVER_CONFIG_DIR: &str = ".hytale-export/server/";
+
+/// Path of the listing in bundles, archives holding the exports of several worlds
+pub const BUNDLE_PATH: &str = ".hytale-export/bundle.json";
+
+/// Current manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub world_name: String,
+    /// Id of the exported world folder, see [`crate::retention::world_id`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_id: Option<String>,
+    pub created_at: String,
+    pub app_version: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ManifestEntry {
+    /// Path of the file inside the archive
+    pub path: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 hash of the file contents
+    pub sha256: String,
+}
+
+impl ExportManifest {
+    pub fn new(world_name: &str) -> Self {
+        Self {
+            format_version: MANIFEST_VERSION,
+            world_name: world_name.to_string(),
+            world_id: None,
+            created_at: chrono::Local::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            files: Vec::new(),
+        }
+    }
+
+    /// The manifest without its file list, stored unencrypted next to an encrypted one
+    pub fn stub(&self) -> Self {
+        Self {
+            files: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Entries holding server config files
+    pub fn server_configs(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.files
+            .iter()
+            .filter(|entry| entry.path.starts_with(SERVER_CONFIG_DIR))
+    }
+}
+
+/// Listing of a bundle: the archive of each world is stored as a separate entry
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub created_at: String,
+    pub worlds: Vec<BundleWorld>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BundleWorld {
+    pub world_name: String,
+    /// Name of the world's archive inside the bundle
+    pub archive: String,
+}
+
+/// Checks whether an archive entry belongs to the export metadata
+pub fn is_metadata_entry(name: &str) -> bool {
+    name.starts_with(METADATA_DIR)
+}
@@ -1,18 +1,154 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::manifest::ExportManifest;
+use crate::signing::SignatureStatus;
 
 #[derive(Clone)]
 pub struct WorldInfo {
     pub name: String,
     pub path: PathBuf,
+    /// Size and last played time, filled in by the background scan
+    pub details: Option<WorldDetails>,
+    /// Name of the save root or server profile the world was found in
+    pub source: String,
+    /// Install directory of the dedicated server the world belongs to
+    pub server_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldDetails {
     pub size: u64,
     pub last_played: Option<String>,
+    /// Number of in-game backups
+    pub backup_count: usize,
+}
+
+/// Column the world list is sorted by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SortColumn {
+    #[default]
+    Name,
+    Size,
+    LastPlayed,
+    Backups,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldSort {
+    pub column: SortColumn,
+    pub ascending: bool,
 }
 
+impl Default for WorldSort {
+    fn default() -> Self {
+        Self {
+            column: SortColumn::Name,
+            ascending: true,
+        }
+    }
+}
+
+/// One of the game's own backups of a world
 #[derive(Clone)]
 pub struct BackupInfo {
     pub name: String,
     pub path: PathBuf,
     pub size: u64,
+    /// Creation time, on file systems that record it
+    pub created: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+    /// Time in the file name, which the game names backups by
+    pub name_time: Option<NaiveDateTime>,
+}
+
+impl BackupInfo {
+    /// When the backup was made: the time in its name, or else the file's times
+    pub fn time(&self) -> Option<DateTime<Local>> {
+        self.name_time
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+            .or_else(|| self.created.or(self.modified).map(DateTime::from))
+    }
+}
+
+/// Column the in-game backups are sorted by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BackupSortColumn {
+    #[default]
+    Time,
+    Name,
+    Size,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupSort {
+    pub column: BackupSortColumn,
+    pub ascending: bool,
+}
+
+impl Default for BackupSort {
+    fn default() -> Self {
+        Self {
+            column: BackupSortColumn::Time,
+            ascending: false,
+        }
+    }
+}
+
+/// File format of an in-game backup, recognized by its first bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackupFormat {
+    Zip,
+    Gzip,
+    Unknown,
+}
+
+/// Format of an in-game backup and the number of files in it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArchiveDetails {
+    pub format: BackupFormat,
+    /// Number of files in the archive, if it could be read
+    pub entry_count: Option<usize>,
+}
+
+/// Contents of an in-game backup, read before it is restored
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameBackupInfo {
+    pub entry_count: usize,
+    /// Folder in the archive that holds the world's files, if they are not at the top
+    pub root: Option<String>,
+    /// Files and folders at the top of the backed up world, sorted
+    pub top_level: Vec<String>,
+}
+
+/// What restoring a backup does to the top level of a world
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RestorePlan {
+    /// In the world and in the backup
+    pub replaced: Vec<String>,
+    /// In the world but not in the backup
+    pub removed: Vec<String>,
+    /// Only in the backup
+    pub added: Vec<String>,
+    /// Folders of the world that a restore never touches
+    pub kept: Vec<String>,
+}
+
+/// In-game backup waiting for confirmation in the restore dialog
+#[derive(Clone)]
+pub struct PendingRestore {
+    pub backup: BackupInfo,
+    pub world_path: PathBuf,
+    pub info: GameBackupInfo,
+    pub plan: RestorePlan,
+    /// The game or a server seemed to use the world when the dialog was opened
+    pub in_use: bool,
+    /// Restore into a new world next to the original instead of replacing it
+    pub as_new_world: bool,
+    pub new_world_name: String,
 }
 
 #[derive(Clone)]
@@ -22,24 +158,200 @@ pub struct LogInfo {
     pub content: String,
 }
 
+/// Backups and latest log of the selected world, read once and shared by the tabs
+pub struct WorldView {
+    pub path: PathBuf,
+    pub backups: Vec<BackupInfo>,
+    pub log: Option<LogInfo>,
+}
+
+/// Archive selected for import, waiting for confirmation in the import dialog
 #[derive(Clone)]
-pub struct BackupProgress {
-    pub current: usize,
-    pub total: usize,
-    pub current_file: String,
-    pub is_running: bool,
-    pub result: Option<Result<String, String>>,
+pub struct PendingImport {
+    pub zip_path: PathBuf,
+    pub world_name: String,
+    pub info: ArchiveInfo,
+    pub password: String,
+    /// Save root or server directory the world is imported into
+    pub target_root: PathBuf,
+    /// Restore server config files when importing into a server
+    pub restore_server_config: bool,
 }
 
-impl Default for BackupProgress {
-    fn default() -> Self {
-        Self {
-            current: 0,
-            total: 0,
-            current_file: String::new(),
-            is_running: false,
-            result: None,
+/// Encrypted archive waiting for its password before it is verified
+#[derive(Clone)]
+pub struct PendingVerify {
+    pub zip_path: PathBuf,
+    pub world_name: String,
+    pub info: ArchiveInfo,
+    pub password: String,
+}
+
+/// Metadata read from an archive before it is imported
+#[derive(Clone)]
+pub struct ArchiveInfo {
+    /// Whether the archive contains encrypted entries and needs a password
+    pub encrypted: bool,
+    /// The manifest, or only its stub if the full manifest is encrypted
+    pub manifest: Option<ExportManifest>,
+    /// Password-protected ZIP holding the full manifest, see [`crate::backup::unlock_manifest`]
+    pub encrypted_manifest: Option<Vec<u8>>,
+    pub signature: SignatureStatus,
+}
+
+/// Compression method for exported archives
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Compression {
+    #[default]
+    Deflated,
+    Zstd,
+    Stored,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 3] = [
+        Compression::Deflated,
+        Compression::Zstd,
+        Compression::Stored,
+    ];
+
+    pub fn method(self) -> zip::CompressionMethod {
+        match self {
+            Compression::Deflated => zip::CompressionMethod::Deflated,
+            Compression::Zstd => zip::CompressionMethod::Zstd,
+            Compression::Stored => zip::CompressionMethod::Stored,
         }
     }
+
+    /// Supported compression levels, if the method has any
+    pub fn levels(self) -> Option<RangeInclusive<i64>> {
+        match self {
+            Compression::Deflated => Some(0..=9),
+            Compression::Zstd => Some(1..=22),
+            Compression::Stored => None,
+        }
+    }
+}
+
+/// How often a world is backed up automatically
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleKind {
+    /// Every given number of minutes
+    Interval(u64),
+    /// Cron expression, see [`crate::scheduler::parse_cron`]
+    Cron(String),
+}
+
+/// Automatic backup of one world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    pub world: PathBuf,
+    pub kind: ScheduleKind,
+}
+
+/// Which exported archives of a world are kept; rules set to 0 are not used
+///
+/// An archive is kept if any of the count rules keeps it, or if none is set.
+/// The oldest of those are then dropped until they fit into the total size.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Number of newest archives to keep
+    pub keep_last: usize,
+    /// Number of days, weeks and months to keep the newest archive of
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+    /// Total size of all kept archives in MB
+    pub max_total_mb: u64,
+}
+
+/// Retention rules of one world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldRetention {
+    pub world: PathBuf,
+    pub policy: RetentionPolicy,
 }
 
+/// Options controlling what goes into an export and how it is checked
+#[derive(Clone)]
+pub struct ExportOptions {
+    pub include_logs: bool,
+    pub include_backups: bool,
+    /// Re-open the finished archive and compare every entry with the source data
+    pub verify: bool,
+    /// Encrypt file entries with AES-256 using this password
+    pub password: Option<String>,
+    /// Split the archive into volumes of at most this many bytes
+    pub split_size: Option<u64>,
+    /// Sign the export manifest with the local signing key
+    pub sign: bool,
+    /// Archive from a snapshot of the world instead of the live folder
+    pub snapshot: bool,
+    /// Dedicated server whose config files are exported along with the world
+    pub server_dir: Option<PathBuf>,
+    pub compression: Compression,
+    /// Compression level, or the method's default when not set
+    pub compression_level: Option<i64>,
+    /// Id of the world for the manifest, by which retention finds its exports
+    pub world_id: Option<String>,
+}
+
+/// Outcome of the read-back verification of a finished archive
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerifyOutcome {
+    /// All entries matched; carries the number of checked files
+    Verified(usize),
+    /// The archive could not be read back or an entry did not match
+    Failed(String),
+}
+
+/// Result of a successful export
+#[derive(Clone)]
+pub struct ExportReport {
+    /// Path of the archive, or of its first volume when it was split
+    pub path: PathBuf,
+    pub volume_count: usize,
+    /// Name of the signing identity, if the export was signed
+    pub signed_by: Option<String>,
+    pub verification: Option<VerifyOutcome>,
+    /// Files that changed on disk while they were being archived
+    pub changed_files: Vec<String>,
+}
+
+/// Outcome of exporting one world of a batch
+#[derive(Clone)]
+pub struct BatchResult {
+    pub world_name: String,
+    pub result: Result<ExportReport, String>,
+}
+
+/// Type of a background job
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JobKind {
+    Export,
+    Import,
+    Verify,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum ProgressStage {
+    Snapshotting,
+    #[default]
+    Compressing,
+    Verifying,
+}
+
+#[derive(Clone, Default)]
+pub struct BackupProgress {
+    pub current: usize,
+    pub total: usize,
+    pub current_file: String,
+    pub stage: ProgressStage,
+    /// Whether the job has started; queued jobs have not
+    pub is_running: bool,
+    /// Position in a batch export; `world_count` is 0 for a single export
+    pub world_index: usize,
+    pub world_count: usize,
+    pub world_name: String,
+}
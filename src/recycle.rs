@@ -0,0 +1,414 @@
+//! Deleting files through the trash, so a deletion can be undone
+//!
+//! Files go to the system trash: the recycle bin on Windows, the Finder trash
+//! on macOS and the freedesktop trash on Linux. Files on a drive without a
+//! trash folder are moved into the app's own trash folder in the config
+//! directory instead, whose entries expire after [`LOCAL_TRASH_DAYS`]. Each
+//! entry there is a folder named after the time of the deletion, holding the
+//! file and a note of where it came from. Any other failure of the system
+//! trash is reported rather than worked around.
+//!
+//! Moving many files can take a while, so the app does it through a
+//! [`TrashWorker`] on a background thread.
+
+use eframe::egui;
+use rust_i18n::t;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::config_file;
+
+/// Days a file is kept in the app's own trash
+pub const LOCAL_TRASH_DAYS: u64 = 30;
+
+const LOCAL_TRASH_DIR: &str = "trash";
+
+/// File in a local trash entry with the original path of the deleted file
+const ORIGINAL_PATH_FILE: &str = ".original-path";
+
+/// Whether files can be taken back out of the system trash on this platform
+pub const SYSTEM_TRASH_RESTORE: bool = cfg!(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+));
+
+#[derive(Clone, Debug, PartialEq)]
+enum TrashLocation {
+    /// In the system trash since the given Unix time
+    System { deleted_at: i64 },
+    /// In an entry folder of the app's own trash
+    Local(PathBuf),
+}
+
+/// A file moved to the trash
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrashedFile {
+    pub original: PathBuf,
+    location: TrashLocation,
+}
+
+impl TrashedFile {
+    /// Whether the file went to the app's own trash instead of the system one
+    pub fn in_local_trash(&self) -> bool {
+        matches!(self.location, TrashLocation::Local(_))
+    }
+
+    /// Whether [`restore`] can put the file back
+    pub fn can_restore(&self) -> bool {
+        self.in_local_trash() || SYSTEM_TRASH_RESTORE
+    }
+}
+
+/// Moves a file to the system trash, or to the app's own trash if its drive has none
+pub fn move_to_trash(path: &Path) -> Result<TrashedFile, String> {
+    let deleted_at = unix_time(SystemTime::now()) as i64;
+    match trash::delete(path) {
+        Ok(()) => Ok(TrashedFile {
+            original: path.to_path_buf(),
+            location: TrashLocation::System { deleted_at },
+        }),
+        Err(e) if has_no_trash(&e) => move_to_local_trash(path, &config_file(LOCAL_TRASH_DIR)?),
+        Err(e) => Err(t!("errors.trash_failed", error = e.to_string()).to_string()),
+    }
+}
+
+/// Whether the system trash failed because the file's drive has no trash folder
+///
+/// The freedesktop trash falls back to the home trash for such drives, which
+/// fails because a file cannot be renamed onto another drive.
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+))]
+fn has_no_trash(error: &trash::Error) -> bool {
+    matches!(
+        error,
+        trash::Error::FileSystem { source, .. }
+            if source.kind() == std::io::ErrorKind::CrossesDevices
+    )
+}
+
+#[cfg(not(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+)))]
+fn has_no_trash(_error: &trash::Error) -> bool {
+    false
+}
+
+/// Moves a file into a new entry of a local trash folder
+fn move_to_local_trash(path: &Path, trash_dir: &Path) -> Result<TrashedFile, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| t!("errors.trash_failed", error = path.to_string_lossy()).to_string())?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let entry = trash_dir.join(format!("{}-{:09}", now.as_secs(), now.subsec_nanos()));
+    fs::create_dir_all(&entry)
+        .map_err(|e| t!("errors.create_dir_failed", error = e.to_string()).to_string())?;
+
+    let result = fs::write(
+        entry.join(ORIGINAL_PATH_FILE),
+        path.to_string_lossy().as_bytes(),
+    )
+    .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())
+    .and_then(|_| move_file(path, &entry.join(name)));
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&entry);
+        return Err(e);
+    }
+    Ok(TrashedFile {
+        original: path.to_path_buf(),
+        location: TrashLocation::Local(entry),
+    })
+}
+
+/// Puts a trashed file back where it was
+pub fn restore(file: &TrashedFile) -> Result<(), String> {
+    if file.original.exists() {
+        return Err(t!(
+            "errors.restore_target_exists",
+            path = file.original.to_string_lossy()
+        )
+        .to_string());
+    }
+    match &file.location {
+        TrashLocation::Local(entry) => {
+            let name = file.original.file_name().unwrap_or_default();
+            move_file(&entry.join(name), &file.original)?;
+            let _ = fs::remove_dir_all(entry);
+            Ok(())
+        }
+        TrashLocation::System { deleted_at } => {
+            restore_from_system_trash(&file.original, *deleted_at)
+        }
+    }
+}
+
+/// Result of a [`TrashWorker`] task
+pub enum TrashOutcome {
+    /// Files moved to the trash, and the first error among those that were not
+    Trashed(Vec<TrashedFile>, Option<String>),
+    /// Number of files put back, and the first error among those that were not
+    Restored(usize, Option<String>),
+}
+
+#[derive(Default)]
+struct WorkerState {
+    running: usize,
+    finished: Vec<TrashOutcome>,
+}
+
+/// Moves files to the trash and back on background threads
+#[derive(Clone, Default)]
+pub struct TrashWorker {
+    state: Arc<Mutex<WorkerState>>,
+}
+
+impl TrashWorker {
+    /// Moves files to the trash, going on past files that cannot be moved
+    pub fn trash(&self, paths: Vec<PathBuf>, ctx: &egui::Context) {
+        self.spawn(ctx, move || {
+            let mut trashed = Vec::new();
+            let mut error = None;
+            for path in paths {
+                match move_to_trash(&path) {
+                    Ok(file) => trashed.push(file),
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+            TrashOutcome::Trashed(trashed, error)
+        });
+    }
+
+    /// Puts trashed files back, going on past files that cannot be restored
+    pub fn restore(&self, files: Vec<TrashedFile>, ctx: &egui::Context) {
+        self.spawn(ctx, move || {
+            let mut restored = 0;
+            let mut error = None;
+            for file in &files {
+                match restore(file) {
+                    Ok(()) => restored += 1,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+            TrashOutcome::Restored(restored, error)
+        });
+    }
+
+    /// Whether files are still being moved
+    pub fn is_busy(&self) -> bool {
+        self.state.lock().unwrap().running > 0
+    }
+
+    /// Takes the results of the tasks that finished since the last call
+    pub fn take_finished(&self) -> Vec<TrashOutcome> {
+        std::mem::take(&mut self.state.lock().unwrap().finished)
+    }
+
+    fn spawn(&self, ctx: &egui::Context, task: impl FnOnce() -> TrashOutcome + Send + 'static) {
+        self.state.lock().unwrap().running += 1;
+        let state = Arc::clone(&self.state);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let outcome = task();
+            let mut state = state.lock().unwrap();
+            state.running -= 1;
+            state.finished.push(outcome);
+            ctx.request_repaint();
+        });
+    }
+}
+
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_from_system_trash(original: &Path, deleted_at: i64) -> Result<(), String> {
+    let trash_error =
+        |e: trash::Error| t!("errors.trash_failed", error = e.to_string()).to_string();
+    // Older copies of the same file may be in the trash as well
+    let item = trash::os_limited::list()
+        .map_err(trash_error)?
+        .into_iter()
+        .filter(|item| item.original_path() == original && item.time_deleted >= deleted_at - 1)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| t!("errors.not_in_trash", path = original.to_string_lossy()).to_string())?;
+    trash::os_limited::restore_all([item]).map_err(trash_error)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_from_system_trash(original: &Path, _deleted_at: i64) -> Result<(), String> {
+    Err(t!("errors.not_in_trash", path = original.to_string_lossy()).to_string())
+}
+
+/// Deletes the entries of the app's own trash that have expired
+pub fn purge_local_trash() {
+    if let Ok(trash_dir) = config_file(LOCAL_TRASH_DIR) {
+        purge_expired(
+            &trash_dir,
+            Duration::from_secs(LOCAL_TRASH_DAYS * 24 * 60 * 60),
+            SystemTime::now(),
+        );
+    }
+}
+
+/// Deletes the entries of a local trash folder older than the given age
+fn purge_expired(trash_dir: &Path, max_age: Duration, now: SystemTime) -> usize {
+    let Ok(entries) = fs::read_dir(trash_dir) else {
+        return 0;
+    };
+    let oldest = unix_time(now).saturating_sub(max_age.as_secs());
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let deleted_at = name
+                .to_string_lossy()
+                .split('-')
+                .next()
+                .and_then(|secs| secs.parse::<u64>().ok());
+            deleted_at.is_some_and(|deleted_at| deleted_at < oldest)
+        })
+        .filter(|entry| fs::remove_dir_all(entry.path()).is_ok())
+        .count()
+}
+
+/// Moves a file, copying it when it has to cross to another drive
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| t!("errors.trash_failed", error = e.to_string()).to_string())?;
+    fs::remove_file(from).map_err(|e| {
+        let _ = fs::remove_file(to);
+        t!("errors.trash_failed", error = e.to_string()).to_string()
+    })
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_trash_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash_dir = dir.path().join("trash");
+        let file = dir.path().join("backup/2026-01-12.zip");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "backup").unwrap();
+
+        let trashed = move_to_local_trash(&file, &trash_dir).unwrap();
+        assert!(trashed.in_local_trash());
+        assert!(trashed.can_restore());
+        assert!(!file.exists());
+        assert_eq!(fs::read_dir(&trash_dir).unwrap().count(), 1);
+
+        // A new file in its place is never overwritten
+        fs::write(&file, "newer").unwrap();
+        assert!(restore(&trashed).is_err());
+        fs::remove_file(&file).unwrap();
+
+        restore(&trashed).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "backup");
+        assert_eq!(fs::read_dir(&trash_dir).unwrap().count(), 0);
+    }
+
+    #[cfg(all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    ))]
+    #[test]
+    fn test_has_no_trash() {
+        use std::io::ErrorKind;
+
+        let fs_error = |kind| trash::Error::FileSystem {
+            path: PathBuf::from("/mnt/usb/.Trash-1000"),
+            source: std::io::Error::from(kind),
+        };
+        assert!(has_no_trash(&fs_error(ErrorKind::CrossesDevices)));
+        assert!(!has_no_trash(&fs_error(ErrorKind::PermissionDenied)));
+        assert!(!has_no_trash(&trash::Error::CouldNotAccess {
+            target: "/mnt/usb/a.zip".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_trash_worker() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("2026-01-12.zip");
+        let worker = TrashWorker::default();
+        let ctx = egui::Context::default();
+
+        // Files that cannot be moved are reported, not dropped
+        worker.trash(vec![file.clone()], &ctx);
+        let start = std::time::Instant::now();
+        while worker.is_busy() {
+            assert!(start.elapsed() < Duration::from_secs(10), "trash timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+        match worker.take_finished().as_slice() {
+            [TrashOutcome::Trashed(trashed, Some(_))] => assert!(trashed.is_empty()),
+            _ => panic!("expected a failed move"),
+        }
+        assert!(worker.take_finished().is_empty());
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = UNIX_EPOCH + Duration::from_secs(100 * 86_400);
+        for days in [10, 50, 95] {
+            let entry = dir.path().join(format!("{}-000000000", days * 86_400));
+            fs::create_dir_all(&entry).unwrap();
+            fs::write(entry.join("a.zip"), "").unwrap();
+        }
+        fs::create_dir(dir.path().join("unrelated")).unwrap();
+
+        let max_age = Duration::from_secs(30 * 86_400);
+        assert_eq!(purge_expired(dir.path(), max_age, now), 2);
+        assert!(dir
+            .path()
+            .join(format!("{}-000000000", 95 * 86_400))
+            .exists());
+        assert!(dir.path().join("unrelated").exists());
+    }
+}
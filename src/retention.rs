@@ -0,0 +1,475 @@
+//! Deleting old exports by retention rules
+//!
+//! The archives of a world are found in a folder by the world id in their
+//! manifest, so renamed files and custom filename templates still match, and
+//! worlds of the same name from different save roots are told apart. The id is
+//! derived from the world folder with a key that never leaves this computer,
+//! so archives shared with others do not reveal the folder. The rules
+//! work like grandfather-father-son rotation: the newest archives, plus the
+//! newest archive of each of the last days, weeks and months are kept. Rules
+//! are applied after each export and by the `prune` command.
+
+use chrono::{DateTime, Datelike, Local};
+use rust_i18n::t;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backup::inspect_archive;
+use crate::jobs::JobReport;
+use crate::models::{ExportReport, RetentionPolicy, VerifyOutcome};
+use crate::settings::{load_settings, Settings};
+use crate::utils::{config_file, format_size, write_config_file};
+use crate::volumes::{archive_size, collect_volumes, is_first_volume, is_volume};
+
+/// An exported archive of a world
+#[derive(Clone, Debug, PartialEq)]
+pub struct Archive {
+    /// The archive, or the first volume of a split one
+    pub path: PathBuf,
+    pub created: DateTime<Local>,
+    pub size: u64,
+}
+
+/// File in the config directory with the key world ids are derived from
+const WORLD_KEY_FILE: &str = "world-key";
+
+/// Id of a world folder, by which its exports are found again
+pub fn world_id(world_path: &Path) -> Result<String, String> {
+    Ok(world_id_with_key(&world_key()?, world_path))
+}
+
+fn world_id_with_key(key: &[u8], world_path: &Path) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    hasher.update(world_path.to_string_lossy().as_bytes());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// Loads the key for world ids, creating it on first use
+fn world_key() -> Result<Vec<u8>, String> {
+    let stored = config_file(WORLD_KEY_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| hex::decode(contents.trim()).ok());
+    if let Some(key) = stored {
+        return Ok(key);
+    }
+
+    let mut key = [0u8; 32];
+    getrandom::getrandom(&mut key)
+        .map_err(|e| t!("errors.key_generation_failed", error = e.to_string()).to_string())?;
+    write_config_file(WORLD_KEY_FILE, hex::encode(key).as_bytes())?;
+    Ok(key.to_vec())
+}
+
+/// Finds the archives of a world in a folder by its id, newest first
+pub fn find_archives(directory: &Path, world_id: &str) -> Vec<Archive> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut archives: Vec<Archive> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            is_first_volume(path)
+                || (!is_volume(path) && path.extension().is_some_and(|ext| ext == "zip"))
+        })
+        .filter_map(|path| {
+            let manifest = inspect_archive(&path).ok()?.manifest?;
+            if manifest.world_id.as_deref() != Some(world_id) {
+                return None;
+            }
+            let created = DateTime::parse_from_rfc3339(&manifest.created_at)
+                .map(|time| time.with_timezone(&Local))
+                .or_else(|_| {
+                    fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .map(Into::into)
+                })
+                .ok()?;
+            Some(Archive {
+                size: archive_size(&path),
+                path,
+                created,
+            })
+        })
+        .collect();
+
+    archives.sort_by_key(|archive| std::cmp::Reverse(archive.created));
+    archives
+}
+
+/// Picks the archives the policy does not keep, from a list sorted newest first
+pub fn expired_archives(archives: &[Archive], policy: &RetentionPolicy) -> Vec<Archive> {
+    let mut kept: HashSet<usize> = HashSet::new();
+    let counted = policy.keep_last > 0
+        || policy.keep_daily > 0
+        || policy.keep_weekly > 0
+        || policy.keep_monthly > 0;
+
+    if counted {
+        kept.extend(0..policy.keep_last.min(archives.len()));
+        keep_newest_per_period(archives, policy.keep_daily, &mut kept, |time| {
+            (time.year(), time.ordinal())
+        });
+        keep_newest_per_period(archives, policy.keep_weekly, &mut kept, |time| {
+            let week = time.iso_week();
+            (week.year(), week.week())
+        });
+        keep_newest_per_period(archives, policy.keep_monthly, &mut kept, |time| {
+            (time.year(), time.month())
+        });
+    } else {
+        kept.extend(0..archives.len());
+    }
+
+    if policy.max_total_mb > 0 {
+        let limit = policy.max_total_mb * 1024 * 1024;
+        let mut total = 0;
+        // The newest archive is always kept, even if it alone is too big
+        for (index, archive) in archives.iter().enumerate() {
+            if !kept.contains(&index) {
+                continue;
+            }
+            total += archive.size;
+            if total > limit && index > 0 {
+                kept.remove(&index);
+            }
+        }
+    }
+
+    archives
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !kept.contains(index))
+        .map(|(_, archive)| archive.clone())
+        .collect()
+}
+
+/// Keeps the newest archive of each of the last `count` periods that have one
+fn keep_newest_per_period(
+    archives: &[Archive],
+    count: usize,
+    kept: &mut HashSet<usize>,
+    period: impl Fn(&DateTime<Local>) -> (i32, u32),
+) {
+    let mut last_period = None;
+    let mut periods = 0;
+    for (index, archive) in archives.iter().enumerate() {
+        if periods == count {
+            break;
+        }
+        let current = period(&archive.created);
+        if last_period != Some(current) {
+            last_period = Some(current);
+            periods += 1;
+            kept.insert(index);
+        }
+    }
+}
+
+/// Deletes the expired archives of a world, or only lists them in a dry run
+///
+/// See [`find_archives`] for which archives belong to the world.
+pub fn prune(
+    directory: &Path,
+    world_id: &str,
+    policy: &RetentionPolicy,
+    dry_run: bool,
+) -> Result<Vec<Archive>, String> {
+    let expired = expired_archives(&find_archives(directory, world_id), policy);
+    if !dry_run {
+        for archive in &expired {
+            let volumes = if is_first_volume(&archive.path) {
+                collect_volumes(&archive.path)
+            } else {
+                vec![archive.path.clone()]
+            };
+            for volume in volumes {
+                fs::remove_file(&volume).map_err(|e| {
+                    t!(
+                        "errors.delete_archive_failed",
+                        path = volume.to_string_lossy(),
+                        error = e.to_string()
+                    )
+                    .to_string()
+                })?;
+            }
+        }
+    }
+    Ok(expired)
+}
+
+/// Applies the world's retention rules in the folder of a new archive,
+/// returning the number of deleted archives
+pub fn apply_retention(
+    settings: &Settings,
+    world_path: &Path,
+    archive: &Path,
+) -> Result<usize, String> {
+    let (Some(policy), Some(directory)) = (settings.retention_policy(world_path), archive.parent())
+    else {
+        return Ok(0);
+    };
+    prune(directory, &world_id(world_path)?, policy, false).map(|deleted| deleted.len())
+}
+
+/// Whether an export can be counted on when older ones are deleted
+///
+/// The rules always keep the newest archive, so pruning after a broken export
+/// could delete the good archives and keep only the broken one.
+pub fn safe_to_prune(export: &ExportReport) -> bool {
+    !matches!(export.verification, Some(VerifyOutcome::Failed(_)))
+        && export.changed_files.is_empty()
+}
+
+/// Applies the retention rules after an export and notes the outcome in its report
+pub fn prune_after_export(
+    settings: &Settings,
+    world_path: &Path,
+    export: &ExportReport,
+    report: &mut JobReport,
+) {
+    if settings.retention_policy(world_path).is_none() {
+        return;
+    }
+    if !safe_to_prune(export) {
+        report
+            .message
+            .push_str(&format!("\n{}", t!("app.prune_skipped")));
+        return;
+    }
+    match apply_retention(settings, world_path, &export.path) {
+        Ok(0) => {}
+        Ok(count) => {
+            report
+                .message
+                .push_str(&format!("\n{}", t!("app.archives_pruned", count = count)));
+        }
+        Err(e) => {
+            let warning = format!("{} {}", t!("app.prune_failed"), e);
+            report.message.push_str(&format!("\n{}", warning));
+            report.add_warning(warning);
+        }
+    }
+}
+
+/// Applies the retention rules of all worlds in a folder, for the `prune` command
+pub fn run_prune(destination: Option<PathBuf>, dry_run: bool) -> Result<(), String> {
+    let settings = load_settings()?;
+    let directory = destination
+        .or_else(|| settings.schedule_directory().map(Path::to_path_buf))
+        .ok_or_else(|| t!("errors.no_schedule_dir").to_string())?;
+    if settings.retention.is_empty() {
+        return Err(t!("errors.no_retention").to_string());
+    }
+
+    let mut total = 0;
+    for retention in &settings.retention {
+        let world_id = world_id(&retention.world)?;
+        let expired = prune(&directory, &world_id, &retention.policy, dry_run)?;
+        for archive in &expired {
+            let line = format!(
+                "{} ({}, {})",
+                archive.path.to_string_lossy(),
+                archive.created.format("%Y-%m-%d %H:%M"),
+                format_size(archive.size)
+            );
+            if dry_run {
+                println!("{}", t!("prune.would_delete", archive = line));
+            } else {
+                println!("{}", t!("prune.deleted", archive = line));
+            }
+        }
+        total += expired.len();
+    }
+
+    if dry_run {
+        println!("{}", t!("prune.would_delete_total", count = total));
+    } else {
+        println!("{}", t!("prune.deleted_total", count = total));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// One archive per day at noon, newest first, each 1 MB
+    fn daily_archives(days: i64) -> Vec<Archive> {
+        let newest = Local.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        (0..days)
+            .map(|day| Archive {
+                path: PathBuf::from(format!("{}.zip", day)),
+                created: newest - Duration::days(day),
+                size: 1024 * 1024,
+            })
+            .collect()
+    }
+
+    fn kept(archives: &[Archive], policy: &RetentionPolicy) -> Vec<String> {
+        let expired = expired_archives(archives, policy);
+        archives
+            .iter()
+            .filter(|archive| !expired.contains(archive))
+            .map(|archive| archive.path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_rules_keep_everything() {
+        let archives = daily_archives(5);
+        assert!(expired_archives(&archives, &RetentionPolicy::default()).is_empty());
+    }
+
+    #[test]
+    fn test_grandfather_father_son() {
+        let archives = daily_archives(90);
+        let policy = RetentionPolicy {
+            keep_last: 2,
+            keep_daily: 3,
+            keep_weekly: 2,
+            keep_monthly: 3,
+            ..Default::default()
+        };
+        // 2026-03-31 is a Tuesday, so day 2 is the Sunday ending the week
+        // before; days 31 and 59 are the last days of February and January
+        assert_eq!(
+            kept(&archives, &policy),
+            vec!["0.zip", "1.zip", "2.zip", "31.zip", "59.zip"]
+        );
+    }
+
+    #[test]
+    fn test_max_total_size() {
+        let archives = daily_archives(5);
+        let policy = RetentionPolicy {
+            max_total_mb: 3,
+            ..Default::default()
+        };
+        assert_eq!(kept(&archives, &policy), vec!["0.zip", "1.zip", "2.zip"]);
+
+        // The newest archive survives even when it alone is too big
+        let mut archives = archives;
+        archives[0].size = 10 * 1024 * 1024;
+        assert_eq!(kept(&archives, &policy), vec!["0.zip"]);
+    }
+
+    /// Exports a world with the given id in its manifest
+    fn export_world(world: &Path, archive: &Path, world_id: &str) -> ExportReport {
+        use crate::backup::backup_world_to_path_with_progress;
+        use crate::models::{BackupProgress, Compression, ExportOptions};
+        use std::sync::{Arc, Mutex};
+
+        let options = ExportOptions {
+            include_logs: true,
+            include_backups: true,
+            verify: false,
+            password: None,
+            split_size: None,
+            sign: false,
+            snapshot: false,
+            server_dir: None,
+            compression: Compression::Deflated,
+            compression_level: None,
+            world_id: Some(world_id.to_string()),
+        };
+        let progress = Arc::new(Mutex::new(BackupProgress::default()));
+        let ctx = eframe::egui::Context::default();
+        backup_world_to_path_with_progress(world, archive, &options, &progress, &ctx).unwrap()
+    }
+
+    fn create_world(path: &Path) {
+        fs::create_dir_all(path.join("chunks")).unwrap();
+        fs::write(path.join("chunks/0.region"), b"region").unwrap();
+    }
+
+    #[test]
+    fn test_prune_exported_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("saves/World");
+        create_world(&world);
+        // A world of the same name on a server
+        let namesake = dir.path().join("server/World");
+        create_world(&namesake);
+        let out = dir.path().join("out");
+        fs::create_dir(&out).unwrap();
+        fs::write(out.join("notes.zip"), b"not an archive").unwrap();
+
+        let world_id = world_id_with_key(b"key", &world);
+        let namesake_id = world_id_with_key(b"key", &namesake);
+        assert_ne!(world_id, namesake_id);
+        assert_ne!(world_id, world_id_with_key(b"other key", &world));
+        for index in 0..3 {
+            export_world(&world, &out.join(format!("World_{}.zip", index)), &world_id);
+        }
+        export_world(&namesake, &out.join("World_server.zip"), &namesake_id);
+
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            ..Default::default()
+        };
+        // The manifest carries the id, never the folder
+        let manifest = inspect_archive(&out.join("World_0.zip"))
+            .unwrap()
+            .manifest
+            .unwrap();
+        assert_eq!(manifest.world_id.as_deref(), Some(world_id.as_str()));
+        assert!(!serde_json::to_string(&manifest).unwrap().contains("saves"));
+
+        assert_eq!(find_archives(&out, &world_id).len(), 3);
+        assert_eq!(prune(&out, &world_id, &policy, true).unwrap().len(), 2);
+        assert_eq!(find_archives(&out, &world_id).len(), 3);
+        let other = world_id_with_key(b"key", &dir.path().join("saves/Other"));
+        assert!(prune(&out, &other, &policy, false).unwrap().is_empty());
+
+        let deleted = prune(&out, &world_id, &policy, false).unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert_eq!(find_archives(&out, &world_id).len(), 1);
+        assert_eq!(find_archives(&out, &namesake_id).len(), 1);
+        assert!(out.join("notes.zip").exists());
+    }
+
+    #[test]
+    fn test_failed_export_prunes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("saves/World");
+        create_world(&world);
+        let out = dir.path().join("out");
+        fs::create_dir(&out).unwrap();
+        let world_id = world_id_with_key(b"key", &world);
+        for index in 0..2 {
+            export_world(&world, &out.join(format!("World_{}.zip", index)), &world_id);
+        }
+        let mut export = export_world(&world, &out.join("World_2.zip"), &world_id);
+
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            ..Default::default()
+        };
+        let mut settings = Settings::default();
+        settings.retention.push(crate::models::WorldRetention {
+            world: world.clone(),
+            policy: policy.clone(),
+        });
+        assert!(safe_to_prune(&export));
+
+        export.verification = Some(VerifyOutcome::Failed("entry mismatch".to_string()));
+        assert!(!safe_to_prune(&export));
+        let mut report = JobReport::from_export(&export);
+        prune_after_export(&settings, &world, &export, &mut report);
+        // The rules would delete two archives, but none is touched
+        assert_eq!(prune(&out, &world_id, &policy, true).unwrap().len(), 2);
+        assert_eq!(find_archives(&out, &world_id).len(), 3);
+
+        export.verification = None;
+        export.changed_files = vec!["chunks/0.region".to_string()];
+        assert!(!safe_to_prune(&export));
+    }
+}
@@ -0,0 +1,287 @@
+//! Background calculation of world sizes and last played times
+//!
+//! Walking a big world to add up its size takes seconds, so the world list is
+//! shown right away and the details are filled in by a background thread.
+//! Results are cached per world folder and reused until its modification time
+//! changes. The format and file count of in-game backups are read the same
+//! way, cached per backup file.
+
+use eframe::egui;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+use crate::game_backup::read_archive_details;
+use crate::models::{ArchiveDetails, BackupInfo, WorldDetails};
+use crate::utils::{count_world_backups, get_directory_size, get_last_played};
+
+struct CacheEntry {
+    modified: SystemTime,
+    details: WorldDetails,
+}
+
+#[derive(Default)]
+struct ScanState {
+    /// Incremented for every scan so an outdated scan stops early
+    generation: u64,
+    cache: HashMap<PathBuf, CacheEntry>,
+    /// Details found by the current scan
+    ready: HashMap<PathBuf, WorldDetails>,
+    pending: usize,
+}
+
+/// Calculates world details on a background thread
+#[derive(Clone, Default)]
+pub struct WorldScanner {
+    state: Arc<Mutex<ScanState>>,
+}
+
+impl WorldScanner {
+    /// Starts calculating the details of the given worlds, replacing a running scan
+    pub fn scan(&self, paths: Vec<PathBuf>, ctx: &egui::Context) {
+        let generation = {
+            let mut state = self.state.lock().unwrap();
+            state.generation += 1;
+            state.ready.clear();
+            state.pending = paths.len();
+            state.generation
+        };
+
+        let state = Arc::clone(&self.state);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for path in paths {
+                let modified = directory_modified(&path);
+                let cached = {
+                    let state = state.lock().unwrap();
+                    if state.generation != generation {
+                        return;
+                    }
+                    state
+                        .cache
+                        .get(&path)
+                        .filter(|entry| Some(entry.modified) == modified)
+                        .map(|entry| entry.details.clone())
+                };
+
+                let details = cached.unwrap_or_else(|| WorldDetails {
+                    size: get_directory_size(&path),
+                    last_played: get_last_played(&path),
+                    backup_count: count_world_backups(&path),
+                });
+
+                let mut state = state.lock().unwrap();
+                if state.generation != generation {
+                    return;
+                }
+                if let Some(modified) = modified {
+                    state.cache.insert(
+                        path.clone(),
+                        CacheEntry {
+                            modified,
+                            details: details.clone(),
+                        },
+                    );
+                }
+                state.ready.insert(path, details);
+                state.pending -= 1;
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    /// Details of a world, once the current scan got to it
+    pub fn details(&self, path: &Path) -> Option<WorldDetails> {
+        self.state.lock().unwrap().ready.get(path).cloned()
+    }
+
+    /// Forgets the cached details of a world whose files changed
+    pub fn invalidate(&self, path: &Path) {
+        self.state.lock().unwrap().cache.remove(path);
+    }
+
+    /// Whether the current scan still has worlds left
+    pub fn is_scanning(&self) -> bool {
+        self.state.lock().unwrap().pending > 0
+    }
+}
+
+struct ArchiveEntry {
+    modified: Option<SystemTime>,
+    /// `None` while the backup is being read
+    details: Option<ArchiveDetails>,
+}
+
+#[derive(Default)]
+struct ArchiveState {
+    cache: HashMap<PathBuf, ArchiveEntry>,
+    queue: Vec<(PathBuf, Option<SystemTime>)>,
+    reading: bool,
+}
+
+/// Reads the format and file count of in-game backups on a background thread
+#[derive(Clone, Default)]
+pub struct ArchiveScanner {
+    state: Arc<Mutex<ArchiveState>>,
+}
+
+impl ArchiveScanner {
+    /// Details of a backup, or `None` until the background thread has read them
+    pub fn details(&self, backup: &BackupInfo, ctx: &egui::Context) -> Option<ArchiveDetails> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(entry) = state.cache.get(&backup.path) {
+            if entry.modified == backup.modified {
+                return entry.details;
+            }
+        }
+
+        state.cache.insert(
+            backup.path.clone(),
+            ArchiveEntry {
+                modified: backup.modified,
+                details: None,
+            },
+        );
+        state.queue.push((backup.path.clone(), backup.modified));
+        if !state.reading {
+            state.reading = true;
+            let state = Arc::clone(&self.state);
+            let ctx = ctx.clone();
+            thread::spawn(move || read_archives(&state, &ctx));
+        }
+        None
+    }
+}
+
+/// Reads queued backups until the queue is empty
+fn read_archives(state: &Mutex<ArchiveState>, ctx: &egui::Context) {
+    loop {
+        let Some((path, modified)) = ({
+            let mut state = state.lock().unwrap();
+            let next = state.queue.pop();
+            state.reading = next.is_some();
+            next
+        }) else {
+            return;
+        };
+
+        let details = read_archive_details(&path);
+        let mut state = state.lock().unwrap();
+        // The file may have changed while it was read, then it is queued again
+        if let Some(entry) = state.cache.get_mut(&path) {
+            if entry.modified == modified {
+                entry.details = Some(details);
+            }
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// Newest modification time of a world folder and its direct subfolders
+///
+/// The game writes its files into subfolders such as `chunks` or `logs`, which
+/// updates their modification time but not the one of the world folder.
+pub fn directory_modified(path: &Path) -> Option<SystemTime> {
+    let own = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let children = fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_dir())
+        .filter_map(|metadata| metadata.modified().ok());
+    children.chain([own]).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BackupFormat;
+    use std::time::{Duration, Instant};
+
+    fn wait_for(scanner: &WorldScanner) {
+        let start = Instant::now();
+        while scanner.is_scanning() {
+            assert!(start.elapsed() < Duration::from_secs(10), "scan timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_scan_and_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("world");
+        fs::create_dir_all(world.join("logs")).unwrap();
+        fs::write(world.join("config.json"), [0u8; 100]).unwrap();
+        fs::write(world.join("logs/2026-01-13_19-35-06_server.log"), b"").unwrap();
+        fs::create_dir(world.join("backup")).unwrap();
+        fs::write(world.join("backup/2026-01-12.zip"), b"").unwrap();
+
+        let scanner = WorldScanner::default();
+        let ctx = egui::Context::default();
+        scanner.scan(vec![world.clone()], &ctx);
+        wait_for(&scanner);
+
+        let details = scanner.details(&world).unwrap();
+        assert_eq!(details.size, 100);
+        assert_eq!(details.last_played.as_deref(), Some("2026-01-13 19:35:06"));
+        assert_eq!(details.backup_count, 1);
+
+        // Unchanged folders are answered from the cache
+        {
+            let mut state = scanner.state.lock().unwrap();
+            state.cache.get_mut(&world).unwrap().details.size = 1;
+        }
+        scanner.scan(vec![world.clone()], &ctx);
+        wait_for(&scanner);
+        assert_eq!(scanner.details(&world).unwrap().size, 1);
+
+        // A changed modification time invalidates the cache entry
+        {
+            let mut state = scanner.state.lock().unwrap();
+            state.cache.get_mut(&world).unwrap().modified = SystemTime::UNIX_EPOCH;
+        }
+        scanner.scan(vec![world.clone()], &ctx);
+        wait_for(&scanner);
+        assert_eq!(scanner.details(&world).unwrap().size, 100);
+    }
+
+    #[test]
+    fn test_archive_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("2026-01-12.zip");
+        fs::write(&path, b"PK\x03\x04broken").unwrap();
+        let mut backup = BackupInfo {
+            name: "2026-01-12.zip".to_string(),
+            path: path.clone(),
+            size: 12,
+            created: None,
+            modified: Some(SystemTime::UNIX_EPOCH),
+            name_time: None,
+        };
+
+        let archives = ArchiveScanner::default();
+        let ctx = egui::Context::default();
+        let read = |backup: &BackupInfo| {
+            let start = Instant::now();
+            loop {
+                if let Some(details) = archives.details(backup, &ctx) {
+                    return details;
+                }
+                assert!(start.elapsed() < Duration::from_secs(10), "read timed out");
+                thread::sleep(Duration::from_millis(10));
+            }
+        };
+        assert_eq!(read(&backup).format, BackupFormat::Zip);
+
+        // Unchanged files are answered from the cache
+        fs::write(&path, [0x1f, 0x8b, 0, 0]).unwrap();
+        assert_eq!(read(&backup).format, BackupFormat::Zip);
+
+        // A changed modification time reads the file again
+        backup.modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+        assert_eq!(read(&backup).format, BackupFormat::Gzip);
+    }
+}
@@ -0,0 +1,399 @@
+//! Automatic backups on a schedule
+//!
+//! Each world can be backed up at a fixed interval or on a cron expression.
+//! The app checks the schedules while it is open and queues exports of due
+//! worlds; the `daemon` command runs the same schedules without a window.
+//! The last run of each world is kept in `schedule.json`, so a backup that was
+//! missed while neither was running is made once on the next start. Only one
+//! process runs the automatic backups at a time, the one holding the
+//! [`ScheduleLock`]; the other waits until it is free.
+
+use chrono::{DateTime, Duration, Local, TimeZone};
+use cron::Schedule;
+use eframe::egui;
+use rust_i18n::t;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::backup::backup_world_to_path_with_progress;
+use crate::history::{append_history, HistoryEntry};
+use crate::jobs::{FinishedJob, JobReport};
+use crate::models::{BackupProgress, BackupSchedule, JobKind, ScheduleKind, WorldInfo};
+use crate::retention::prune_after_export;
+use crate::session::SessionMonitor;
+use crate::settings::{external_save_roots, load_settings, merge_save_roots, Settings};
+use crate::utils::{config_file, write_config_file};
+use crate::worlds::load_worlds;
+
+const STATE_FILE: &str = "schedule.json";
+
+const LOCK_FILE: &str = "schedule.lock";
+
+/// Longest time the daemon sleeps before looking at the settings again
+const DAEMON_POLL_SECS: i64 = 60;
+
+/// Time the daemon sleeps while it waits for play sessions to end
+const DAEMON_SESSION_POLL_SECS: i64 = 5;
+
+/// Parses a cron expression
+///
+/// Both the classic five fields (minute, hour, day of month, month, day of
+/// week) and the six or seven fields with seconds and year are accepted.
+pub fn parse_cron(expression: &str) -> Result<Schedule, String> {
+    let expression = expression.trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression)
+        .map_err(|e| t!("errors.invalid_cron", error = e.to_string()).to_string())
+}
+
+impl ScheduleKind {
+    /// First run after the given time, if there is one
+    pub fn next_run(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            ScheduleKind::Interval(minutes) => {
+                Some(after + Duration::minutes((*minutes).max(1) as i64))
+            }
+            ScheduleKind::Cron(expression) => parse_cron(expression).ok()?.after(&after).next(),
+        }
+    }
+}
+
+/// Keeps track of when each scheduled world was last backed up
+#[derive(Default)]
+pub struct Scheduler {
+    /// Unix time of the last run, or of when the schedule was first seen
+    last_runs: HashMap<PathBuf, i64>,
+    changed: bool,
+}
+
+impl Scheduler {
+    /// Loads the last runs from the config directory
+    pub fn load() -> Self {
+        let last_runs = config_file(STATE_FILE)
+            .ok()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            last_runs,
+            changed: false,
+        }
+    }
+
+    /// Stores the last runs if they changed since the last call
+    pub fn save(&mut self) -> Result<(), String> {
+        if !self.changed {
+            return Ok(());
+        }
+        let json = serde_json::to_vec_pretty(&self.last_runs)
+            .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+        write_config_file(STATE_FILE, &json)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    /// Returns the worlds whose backup is due and marks them as run
+    ///
+    /// A schedule seen for the first time starts counting from now.
+    pub fn take_due(&mut self, schedules: &[BackupSchedule], now: DateTime<Local>) -> Vec<PathBuf> {
+        let before = self.last_runs.len();
+        self.last_runs
+            .retain(|world, _| schedules.iter().any(|schedule| schedule.world == *world));
+        self.changed |= self.last_runs.len() != before;
+
+        let mut due = Vec::new();
+        for schedule in schedules {
+            if !self.last_runs.contains_key(&schedule.world) {
+                self.last_runs
+                    .insert(schedule.world.clone(), now.timestamp());
+                self.changed = true;
+                continue;
+            }
+            if self
+                .next_run(schedule)
+                .is_some_and(|next_run| next_run <= now)
+            {
+                self.last_runs
+                    .insert(schedule.world.clone(), now.timestamp());
+                self.changed = true;
+                due.push(schedule.world.clone());
+            }
+        }
+        due
+    }
+
+    /// Next backup of a world, once the schedule has been seen by [`Self::take_due`]
+    pub fn next_run(&self, schedule: &BackupSchedule) -> Option<DateTime<Local>> {
+        let last_run = Local
+            .timestamp_opt(*self.last_runs.get(&schedule.world)?, 0)
+            .single()?;
+        schedule.kind.next_run(last_run)
+    }
+
+    /// Earliest next backup of all schedules
+    pub fn next_due(&self, schedules: &[BackupSchedule]) -> Option<DateTime<Local>> {
+        schedules
+            .iter()
+            .filter_map(|schedule| self.next_run(schedule))
+            .min()
+    }
+}
+
+/// Held by the one process that runs the automatic backups
+///
+/// The operating system releases the lock when the process ends, so a crashed
+/// app or daemon never keeps the other from taking over.
+pub struct ScheduleLock {
+    _file: File,
+}
+
+impl ScheduleLock {
+    /// Takes the lock unless another process holds it
+    pub fn try_acquire() -> Option<Self> {
+        let path = config_file(LOCK_FILE).ok()?;
+        fs::create_dir_all(path.parent()?).ok()?;
+        Self::try_acquire_at(&path)
+    }
+
+    fn try_acquire_at(path: &Path) -> Option<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .ok()?;
+        file.try_lock().ok()?;
+        Some(Self { _file: file })
+    }
+}
+
+/// Exports a world with the settings' options into a folder
+///
+/// Scheduled backups run unattended, so they are never encrypted or signed.
+pub fn scheduled_export(
+    world: &WorldInfo,
+    settings: &Settings,
+    directory: &Path,
+    progress: &Arc<Mutex<BackupProgress>>,
+    ctx: &egui::Context,
+) -> Result<JobReport, String> {
+    let file_name = settings.archive_file_name(world, Some(directory), true)?;
+    let options = settings.export_options(world, None, false);
+    let export = backup_world_to_path_with_progress(
+        &world.path,
+        &directory.join(file_name),
+        &options,
+        progress,
+        ctx,
+    )?;
+    let mut report = JobReport::from_export(&export);
+    prune_after_export(settings, &world.path, &export, &mut report);
+    Ok(report)
+}
+
+/// Runs the scheduled backups without a window until the process is stopped
+///
+/// Played worlds are also backed up when their session ends, if that is
+/// enabled. The settings are read again on every round, so changes made in
+/// the app are picked up. Every backup is recorded in the job history. While
+/// the app runs the automatic backups, the daemon waits for it to close.
+pub fn run_daemon(saves_dirs: &[PathBuf], destination: Option<PathBuf>) -> Result<(), String> {
+    let mut settings = load_settings()?;
+    if settings.schedules.is_empty() && !settings.backup_on_game_exit {
+        return Err(t!("errors.no_schedules").to_string());
+    }
+    // The folder from the start stays in use if it is removed from the settings later
+    let mut directory = destination
+        .clone()
+        .or_else(|| settings.schedule_directory().map(Path::to_path_buf))
+        .ok_or_else(|| t!("errors.no_schedule_dir").to_string())?;
+
+    let external_roots = external_save_roots(saves_dirs);
+    let mut scheduler = Scheduler::default();
+    let mut lock = None;
+    let mut waiting = false;
+    let mut session_monitor: Option<SessionMonitor> = None;
+    let ctx = egui::Context::default();
+    println!("{}", t!("daemon.started", count = settings.schedules.len()));
+    if settings.backup_on_game_exit {
+        println!("{}", t!("daemon.watching_sessions"));
+    }
+
+    loop {
+        // Keep the last good settings while the file cannot be read
+        match load_settings() {
+            Ok(loaded) => settings = loaded,
+            Err(e) => eprintln!("{} {}", t!("app.error"), e),
+        }
+        if destination.is_none() {
+            if let Some(configured) = settings.schedule_directory() {
+                directory = configured.to_path_buf();
+            }
+        }
+
+        if lock.is_none() {
+            lock = ScheduleLock::try_acquire();
+            if lock.is_none() {
+                if !waiting {
+                    println!("{}", t!("daemon.waiting_for_app"));
+                    waiting = true;
+                }
+                thread::sleep(std::time::Duration::from_secs(DAEMON_POLL_SECS as u64));
+                continue;
+            }
+            waiting = false;
+            // The app may have made backups while it held the lock
+            scheduler = Scheduler::load();
+        }
+
+        let roots = merge_save_roots(&settings.save_roots, &external_roots);
+        let worlds = load_worlds(&roots, &settings.server_profiles);
+        let mut due = scheduler.take_due(&settings.schedules, Local::now());
+        if settings.backup_on_game_exit {
+            let monitor = session_monitor.get_or_insert_with(|| SessionMonitor::new(&ctx));
+            monitor.set_worlds(worlds.iter().map(|world| world.path.clone()).collect());
+            for path in monitor.take_ended() {
+                if !due.contains(&path) {
+                    due.push(path);
+                }
+            }
+        } else {
+            session_monitor = None;
+        }
+
+        for path in due {
+            let Some(world) = worlds.iter().find(|world| world.path == path) else {
+                eprintln!(
+                    "{}",
+                    t!(
+                        "errors.scheduled_world_missing",
+                        path = path.to_string_lossy()
+                    )
+                );
+                continue;
+            };
+
+            println!("{}", t!("daemon.exporting", world = world.name));
+            let progress = Arc::new(Mutex::new(BackupProgress::default()));
+            let started_at = Local::now();
+            let start = Instant::now();
+            let result = scheduled_export(world, &settings, &directory, &progress, &ctx);
+            match &result {
+                Ok(report) => println!("{}", report.message),
+                Err(e) => eprintln!("{} {}", t!("app.error"), e),
+            }
+
+            let entry = HistoryEntry::from_job(&FinishedJob {
+                kind: JobKind::Export,
+                world: world.name.clone(),
+                started_at,
+                duration: start.elapsed(),
+                result,
+            });
+            if let Err(e) = append_history(vec![entry]) {
+                eprintln!("{} {}", t!("app.error"), e);
+            }
+        }
+        if let Err(e) = scheduler.save() {
+            eprintln!("{} {}", t!("app.error"), e);
+        }
+
+        let longest = if session_monitor.is_some() {
+            DAEMON_SESSION_POLL_SECS
+        } else {
+            DAEMON_POLL_SECS
+        };
+        let now = Local::now();
+        let wait = scheduler
+            .next_due(&settings.schedules)
+            .map_or(longest, |next| (next - now).num_seconds().clamp(1, longest));
+        thread::sleep(std::time::Duration::from_secs(wait as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 1, 13, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn schedule(world: &str, kind: ScheduleKind) -> BackupSchedule {
+        BackupSchedule {
+            world: PathBuf::from(world),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_parse_cron() {
+        assert!(parse_cron("0 3 * * *").is_ok());
+        assert!(parse_cron("0 30 3 * * Mon-Fri").is_ok());
+        assert!(parse_cron("every day").is_err());
+
+        let next = ScheduleKind::Cron("30 3 * * *".to_string()).next_run(at(12, 0));
+        assert_eq!(next, Some(at(3, 30) + Duration::days(1)));
+        assert_eq!(
+            ScheduleKind::Cron("nope".to_string()).next_run(at(12, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_take_due() {
+        let mut scheduler = Scheduler::default();
+        let schedules = vec![
+            schedule("/saves/Hourly", ScheduleKind::Interval(60)),
+            schedule(
+                "/saves/Nightly",
+                ScheduleKind::Cron("0 3 * * *".to_string()),
+            ),
+        ];
+
+        // New schedules start counting when they are first seen
+        assert!(scheduler.take_due(&schedules, at(1, 0)).is_empty());
+        assert_eq!(scheduler.next_due(&schedules), Some(at(2, 0)));
+        assert!(scheduler.take_due(&schedules, at(1, 59)).is_empty());
+        assert_eq!(
+            scheduler.take_due(&schedules, at(2, 0)),
+            vec![PathBuf::from("/saves/Hourly")]
+        );
+
+        // Runs missed in between are made up once
+        assert_eq!(
+            scheduler.take_due(&schedules, at(5, 0)),
+            vec![
+                PathBuf::from("/saves/Hourly"),
+                PathBuf::from("/saves/Nightly")
+            ]
+        );
+        assert!(scheduler.take_due(&schedules, at(5, 30)).is_empty());
+
+        // Removed schedules are forgotten
+        scheduler.take_due(&schedules[..1], at(5, 30));
+        assert_eq!(scheduler.next_run(&schedules[1]), None);
+    }
+
+    #[test]
+    fn test_schedule_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCK_FILE);
+
+        let lock = ScheduleLock::try_acquire_at(&path).unwrap();
+        assert!(ScheduleLock::try_acquire_at(&path).is_none());
+        drop(lock);
+        assert!(ScheduleLock::try_acquire_at(&path).is_some());
+    }
+}
@@ -0,0 +1,63 @@
+//! Dedicated server installs
+//!
+//! A dedicated server keeps its universe (worlds and player data) in its own
+//! install directory, next to JSON config files such as `config.json`,
+//! `permissions.json` or `whitelist.json`. Universes are exported like any
+//! other world; the config files travel along in the export metadata.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Finds the universe folders of a server: direct subfolders with a `worlds` folder
+pub fn find_universes(server_dir: &Path) -> Vec<PathBuf> {
+    let mut universes: Vec<PathBuf> = fs::read_dir(server_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.join("worlds").is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    universes.sort();
+    universes
+}
+
+/// Lists the config files in the top level of a server install
+pub fn server_config_files(server_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(server_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_universes_and_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = dir.path();
+        fs::create_dir_all(server.join("universe/worlds/default")).unwrap();
+        fs::create_dir_all(server.join("mods")).unwrap();
+        fs::create_dir_all(server.join("logs")).unwrap();
+        fs::write(server.join("config.json"), b"{}").unwrap();
+        fs::write(server.join("whitelist.json"), b"[]").unwrap();
+        fs::write(server.join("HytaleServer.jar"), b"").unwrap();
+
+        assert_eq!(find_universes(server), vec![server.join("universe")]);
+        assert_eq!(
+            server_config_files(server),
+            vec![server.join("config.json"), server.join("whitelist.json")]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Detection of finished play sessions
+//!
+//! A background thread polls the running processes and the worlds every few
+//! seconds. A world counts as played when its newest log grows, or when its
+//! folder changes while the game is running. Its session ends when the Hytale
+//! client or server process exits, or when its log stops growing after a
+//! `Shutdown Modules` line, which also covers servers whose process is not
+//! visible from here.
+
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::consistency::running_game_processes;
+use crate::scanner::directory_modified;
+use crate::utils::get_latest_log_path;
+
+/// Time between two looks at the processes and worlds
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Line the game writes to the log when a world is shut down
+const SHUTDOWN_MARKER: &str = "Shutdown Modules";
+
+/// How much of the end of a log is searched for the shutdown line
+const LOG_TAIL_BYTES: u64 = 4096;
+
+/// What could be seen of a world at one point in time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorldActivity {
+    pub modified: Option<SystemTime>,
+    pub log: Option<PathBuf>,
+    pub log_len: u64,
+    /// The end of the newest log contains the shutdown line
+    pub shut_down: bool,
+}
+
+impl WorldActivity {
+    pub fn read(world_path: &Path) -> Self {
+        let log = get_latest_log_path(world_path);
+        let (log_len, shut_down) = log
+            .as_deref()
+            .and_then(|path| read_log_tail(path).ok())
+            .unwrap_or_default();
+        Self {
+            modified: directory_modified(world_path),
+            log,
+            log_len,
+            shut_down,
+        }
+    }
+}
+
+/// Length of a log and whether its end contains the shutdown line
+fn read_log_tail(path: &Path) -> std::io::Result<(u64, bool)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(LOG_TAIL_BYTES)))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    Ok((
+        len,
+        String::from_utf8_lossy(&tail).contains(SHUTDOWN_MARKER),
+    ))
+}
+
+/// Turns observations of the game and the worlds into ended sessions
+#[derive(Default)]
+pub struct SessionTracker {
+    game_running: bool,
+    last: HashMap<PathBuf, WorldActivity>,
+    /// Worlds that changed since their session started
+    played: HashSet<PathBuf>,
+}
+
+impl SessionTracker {
+    /// Takes one observation, returns the worlds whose session just ended
+    pub fn observe(
+        &mut self,
+        game_running: bool,
+        worlds: HashMap<PathBuf, WorldActivity>,
+    ) -> Vec<PathBuf> {
+        let mut ended = Vec::new();
+        for (path, activity) in &worlds {
+            let Some(last) = self.last.get(path) else {
+                continue;
+            };
+            let log_grew = activity.log != last.log || activity.log_len != last.log_len;
+            // Without the game running, only a log shows play on a server elsewhere
+            if log_grew || (game_running && activity.modified != last.modified) {
+                self.played.insert(path.clone());
+            }
+            if !log_grew && activity.shut_down && self.played.remove(path) {
+                ended.push(path.clone());
+            }
+        }
+
+        if self.game_running && !game_running {
+            ended.extend(self.played.drain());
+        }
+        self.played.retain(|path| worlds.contains_key(path));
+        self.game_running = game_running;
+        self.last = worlds;
+
+        ended.sort();
+        ended
+    }
+}
+
+#[derive(Default)]
+struct MonitorState {
+    worlds: Vec<PathBuf>,
+    ended: Vec<PathBuf>,
+}
+
+/// Watches for play sessions ending on a background thread
+///
+/// The thread stops once the monitor is dropped.
+pub struct SessionMonitor {
+    state: Arc<Mutex<MonitorState>>,
+}
+
+impl SessionMonitor {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let state = Arc::new(Mutex::new(MonitorState::default()));
+        let weak = Arc::downgrade(&state);
+        let ctx = ctx.clone();
+        thread::spawn(move || run_monitor(weak, ctx));
+        Self { state }
+    }
+
+    /// Sets the worlds to watch
+    pub fn set_worlds(&self, worlds: Vec<PathBuf>) {
+        self.state.lock().unwrap().worlds = worlds;
+    }
+
+    /// Takes the worlds whose play session ended since the last call
+    pub fn take_ended(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.state.lock().unwrap().ended)
+    }
+}
+
+fn run_monitor(state: Weak<Mutex<MonitorState>>, ctx: egui::Context) {
+    let mut tracker = SessionTracker::default();
+    loop {
+        let Some(worlds) = state
+            .upgrade()
+            .map(|state| state.lock().unwrap().worlds.clone())
+        else {
+            return;
+        };
+
+        let game_running = !running_game_processes().is_empty();
+        let activity = worlds
+            .into_iter()
+            .map(|path| {
+                let activity = WorldActivity::read(&path);
+                (path, activity)
+            })
+            .collect();
+        let ended = tracker.observe(game_running, activity);
+
+        if !ended.is_empty() {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            state.lock().unwrap().ended.extend(ended);
+            ctx.request_repaint();
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn activity(modified_secs: u64, log_len: u64, shut_down: bool) -> WorldActivity {
+        WorldActivity {
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(modified_secs)),
+            log: Some(PathBuf::from("logs/1.log")),
+            log_len,
+            shut_down,
+        }
+    }
+
+    fn observe(
+        tracker: &mut SessionTracker,
+        game_running: bool,
+        worlds: &[(&str, WorldActivity)],
+    ) -> Vec<PathBuf> {
+        tracker.observe(
+            game_running,
+            worlds
+                .iter()
+                .map(|(path, activity)| (PathBuf::from(path), activity.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_session_ends_when_game_exits() {
+        let mut tracker = SessionTracker::default();
+        let idle = ("B", activity(1, 10, false));
+        assert!(observe(
+            &mut tracker,
+            true,
+            &[("A", activity(1, 10, false)), idle.clone()]
+        )
+        .is_empty());
+        assert!(observe(
+            &mut tracker,
+            true,
+            &[("A", activity(2, 10, false)), idle.clone()]
+        )
+        .is_empty());
+        let played = ("A", activity(2, 10, false));
+        assert_eq!(
+            observe(&mut tracker, false, &[played.clone(), idle.clone()]),
+            vec![PathBuf::from("A")]
+        );
+        // Nothing is reported twice
+        assert!(observe(&mut tracker, false, &[played, idle]).is_empty());
+    }
+
+    #[test]
+    fn test_changes_without_game_are_not_play() {
+        let mut tracker = SessionTracker::default();
+        observe(&mut tracker, false, &[("A", activity(1, 10, true))]);
+        observe(&mut tracker, false, &[("A", activity(2, 10, true))]);
+        assert!(observe(&mut tracker, false, &[("A", activity(2, 10, true))]).is_empty());
+    }
+
+    #[test]
+    fn test_session_ends_with_shutdown_line() {
+        let mut tracker = SessionTracker::default();
+        observe(&mut tracker, false, &[("A", activity(1, 10, false))]);
+        assert!(observe(&mut tracker, false, &[("A", activity(1, 20, false))]).is_empty());
+        // The log must stop growing after the shutdown line
+        assert!(observe(&mut tracker, false, &[("A", activity(1, 30, true))]).is_empty());
+        assert_eq!(
+            observe(&mut tracker, false, &[("A", activity(1, 30, true))]),
+            vec![PathBuf::from("A")]
+        );
+        assert!(observe(&mut tracker, false, &[("A", activity(1, 30, true))]).is_empty());
+    }
+
+    #[test]
+    fn test_read_activity() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("logs")).unwrap();
+        fs::write(dir.path().join("logs/2026-01-12.log"), "old").unwrap();
+        fs::write(
+            dir.path().join("logs/2026-01-13.log"),
+            "[INFO] Saving\n-=|Shutdown Modules|86.0\n",
+        )
+        .unwrap();
+
+        let activity = WorldActivity::read(dir.path());
+        assert_eq!(activity.log, Some(dir.path().join("logs/2026-01-13.log")));
+        assert!(activity.shut_down);
+        assert!(activity.modified.is_some());
+    }
+}
@@ -0,0 +1,362 @@
+//! Persistent application settings
+//!
+//! Settings are stored as JSON in the application's config directory. Save
+//! roots can additionally come from the `HYTALE_SAVES_PATH` environment
+//! variable and the `--saves-dir` command line flag; those are used for the
+//! current run only and never written to the settings file.
+
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backup::get_hytale_worlds_path;
+use crate::models::{
+    BackupSchedule, BackupSort, Compression, ExportOptions, RetentionPolicy, WorldInfo,
+    WorldRetention, WorldSort,
+};
+use crate::retention::world_id;
+use crate::template::{render_unique, uses_placeholder, TemplateContext};
+use crate::utils::{config_file, get_directory_size, write_config_file};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Environment variable with additional save roots, separated like `PATH`
+pub const SAVES_PATH_ENV: &str = "HYTALE_SAVES_PATH";
+
+/// Default volume size for split exports, just below the FAT32 file size limit
+pub const DEFAULT_SPLIT_SIZE_MB: u64 = 4095;
+
+/// Default name of exported archives, without the extension
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{world}_{timestamp}";
+
+/// A folder containing Hytale worlds
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SaveRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// A dedicated server install whose universes can be backed up
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServerProfile {
+    pub name: String,
+    /// The server's install directory, holding its config files
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Save roots configured by the user, in display order
+    pub save_roots: Vec<SaveRoot>,
+    pub server_profiles: Vec<ServerProfile>,
+
+    pub include_logs: bool,
+    pub include_backups: bool,
+    pub verify_after_export: bool,
+    pub split_export: bool,
+    /// Maximum volume size in MB when splitting exports
+    pub split_size_mb: u64,
+    /// Archive from a quick snapshot so the game can keep running
+    pub snapshot_export: bool,
+    /// Write batch exports into one bundle instead of one archive per world
+    pub batch_bundle: bool,
+    pub compression: Compression,
+    /// Compression level, or the method's default when not set
+    pub compression_level: Option<i64>,
+    /// Number of exports, imports and verifications that may run at once
+    pub max_parallel_jobs: usize,
+
+    /// Folder the save dialog opens in; the last used folder when not set
+    pub default_export_dir: Option<PathBuf>,
+    pub last_export_dir: Option<PathBuf>,
+    /// Name of exported archives without the extension, see [`crate::template`]
+    pub filename_template: String,
+    /// Tags available to filename templates as `{tags}`
+    pub export_tags: Vec<String>,
+
+    /// Automatic backups, at most one per world
+    pub schedules: Vec<BackupSchedule>,
+    /// Folder scheduled backups are written to; the default export folder when not set
+    pub schedule_dir: Option<PathBuf>,
+    /// Back up played worlds when the game or a server shuts down
+    pub backup_on_game_exit: bool,
+    /// Rules for deleting old exports, at most one per world
+    pub retention: Vec<WorldRetention>,
+
+    pub selected_tab: usize,
+    pub hide_info_logs: bool,
+    /// Worlds pinned to the top of the world list
+    pub favorite_worlds: Vec<PathBuf>,
+    pub world_sort: WorldSort,
+    pub backup_sort: BackupSort,
+    /// Show the in-game backups under a heading for each day
+    pub group_backups_by_day: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            save_roots: get_hytale_worlds_path()
+                .map(|path| {
+                    vec![SaveRoot {
+                        name: "Hytale".to_string(),
+                        path,
+                    }]
+                })
+                .unwrap_or_default(),
+            server_profiles: Vec::new(),
+            include_logs: true,
+            include_backups: true,
+            verify_after_export: false,
+            split_export: false,
+            split_size_mb: DEFAULT_SPLIT_SIZE_MB,
+            snapshot_export: false,
+            batch_bundle: false,
+            compression: Compression::default(),
+            compression_level: None,
+            max_parallel_jobs: 1,
+            default_export_dir: None,
+            last_export_dir: None,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            export_tags: Vec::new(),
+            schedules: Vec::new(),
+            schedule_dir: None,
+            backup_on_game_exit: false,
+            retention: Vec::new(),
+            selected_tab: 0,
+            hide_info_logs: false,
+            favorite_worlds: Vec::new(),
+            world_sort: WorldSort::default(),
+            backup_sort: BackupSort::default(),
+            group_backups_by_day: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Export options from these settings for a world
+    pub fn export_options(
+        &self,
+        world: &WorldInfo,
+        password: Option<String>,
+        sign: bool,
+    ) -> ExportOptions {
+        ExportOptions {
+            include_logs: self.include_logs,
+            include_backups: self.include_backups,
+            verify: self.verify_after_export,
+            password,
+            sign,
+            split_size: self
+                .split_export
+                .then_some(self.split_size_mb * 1024 * 1024),
+            snapshot: self.snapshot_export,
+            server_dir: world.server_dir.clone(),
+            compression: self.compression,
+            compression_level: self.compression_level,
+            world_id: world_id(&world.path).ok(),
+        }
+    }
+
+    /// Renders the filename template for a world
+    ///
+    /// The size comes from the background scan. If that has not finished, the
+    /// world is only measured with `measure_size` and if the template has a
+    /// `{size}`, since that walks through the whole world.
+    pub fn archive_file_name(
+        &self,
+        world: &WorldInfo,
+        directory: Option<&Path>,
+        measure_size: bool,
+    ) -> Result<String, String> {
+        let hostname = sysinfo::System::host_name().unwrap_or_default();
+        let size = world
+            .details
+            .as_ref()
+            .map(|details| details.size)
+            .or_else(|| {
+                (measure_size && uses_placeholder(&self.filename_template, "size"))
+                    .then(|| get_directory_size(&world.path))
+            });
+        let context = TemplateContext {
+            world: &world.name,
+            time: chrono::Local::now(),
+            hostname: &hostname,
+            size,
+            tags: &self.export_tags,
+        };
+        render_unique(&self.filename_template, &context, directory, "zip")
+    }
+
+    /// Retention rules of a world, if it has any
+    pub fn retention_policy(&self, world_path: &Path) -> Option<&RetentionPolicy> {
+        self.retention
+            .iter()
+            .find(|retention| retention.world == world_path)
+            .map(|retention| &retention.policy)
+    }
+
+    /// Folder scheduled and session backups are written to
+    pub fn schedule_directory(&self) -> Option<&Path> {
+        self.schedule_dir
+            .as_deref()
+            .or(self.default_export_dir.as_deref())
+    }
+}
+
+/// Loads the settings, falling back to the defaults on first start
+///
+/// A settings file that cannot be read is an error rather than the defaults,
+/// so the next save never overwrites it unnoticed.
+pub fn load_settings() -> Result<Settings, String> {
+    match config_file(SETTINGS_FILE) {
+        Ok(path) => read_settings(&path),
+        Err(_) => Ok(Settings::default()),
+    }
+}
+
+fn read_settings(path: &Path) -> Result<Settings, String> {
+    let settings_error = |error: String| {
+        t!(
+            "errors.settings_invalid",
+            path = path.to_string_lossy(),
+            error = error
+        )
+        .to_string()
+    };
+    match fs::read(path) {
+        Ok(contents) => {
+            serde_json::from_slice(&contents).map_err(|e| settings_error(e.to_string()))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(settings_error(e.to_string())),
+    }
+}
+
+/// Moves an unreadable settings file aside so the defaults can be saved
+///
+/// Returns the path the file was moved to.
+pub fn set_aside_settings() -> Result<PathBuf, String> {
+    let path = config_file(SETTINGS_FILE)?;
+    let broken = path.with_extension("json.broken");
+    fs::rename(&path, &broken)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+    Ok(broken)
+}
+
+/// Stores the settings
+pub fn save_settings(settings: &Settings) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(settings)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+    write_config_file(SETTINGS_FILE, &json)
+}
+
+/// Parses the save roots listed in the environment variable
+pub fn parse_env_roots(value: &OsStr) -> Vec<SaveRoot> {
+    std::env::split_paths(value)
+        .filter(|path| !path.as_os_str().is_empty())
+        .map(|path| SaveRoot {
+            name: SAVES_PATH_ENV.to_string(),
+            path,
+        })
+        .collect()
+}
+
+/// Save roots that come from the environment and the command line
+pub fn external_save_roots(cli_dirs: &[PathBuf]) -> Vec<SaveRoot> {
+    let mut roots = std::env::var_os(SAVES_PATH_ENV)
+        .map(|value| parse_env_roots(&value))
+        .unwrap_or_default();
+    roots.extend(cli_dirs.iter().map(|path| SaveRoot {
+        name: "--saves-dir".to_string(),
+        path: path.clone(),
+    }));
+    roots
+}
+
+/// Combines configured and external save roots, dropping duplicate paths
+pub fn merge_save_roots(configured: &[SaveRoot], external: &[SaveRoot]) -> Vec<SaveRoot> {
+    let mut roots: Vec<SaveRoot> = Vec::new();
+    for root in configured.iter().chain(external) {
+        if !roots.iter().any(|existing| existing.path == root.path) {
+            roots.push(root.clone());
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    fn root(name: &str, path: &str) -> SaveRoot {
+        SaveRoot {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_parse_env_roots() {
+        let value = std::env::join_paths(["/srv/hytale/saves", "/home/me/worlds"]).unwrap();
+        assert_eq!(
+            parse_env_roots(&value),
+            vec![
+                root(SAVES_PATH_ENV, "/srv/hytale/saves"),
+                root(SAVES_PATH_ENV, "/home/me/worlds"),
+            ]
+        );
+        assert!(parse_env_roots(&OsString::new()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_save_roots() {
+        let configured = vec![root("Main", "/a"), root("Server", "/b")];
+        let external = vec![root(SAVES_PATH_ENV, "/b"), root("--saves-dir", "/c")];
+
+        assert_eq!(
+            merge_save_roots(&configured, &external),
+            vec![
+                root("Main", "/a"),
+                root("Server", "/b"),
+                root("--saves-dir", "/c")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_without_roots_field() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_partial_settings_keep_defaults() {
+        // Settings files from older versions lack the newer fields
+        let settings: Settings =
+            serde_json::from_str(r#"{"save_roots": [], "include_logs": false}"#).unwrap();
+        assert!(settings.save_roots.is_empty());
+        assert!(!settings.include_logs);
+        assert!(settings.include_backups);
+        assert_eq!(settings.split_size_mb, DEFAULT_SPLIT_SIZE_MB);
+        assert_eq!(settings.filename_template, DEFAULT_FILENAME_TEMPLATE);
+    }
+
+    #[test]
+    fn test_read_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+        assert_eq!(read_settings(&path).unwrap(), Settings::default());
+
+        fs::write(&path, r#"{"include_logs": false}"#).unwrap();
+        assert!(!read_settings(&path).unwrap().include_logs);
+
+        // A half-written file is reported instead of replaced by the defaults
+        fs::write(&path, r#"{"include_logs": fal"#).unwrap();
+        assert!(read_settings(&path).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Ed25519 signing of export manifests
+//!
+//! The signing identity (name and secret key) and the list of trusted public
+//! keys live as JSON files in the application's config directory. Exports are
+//! signed by signing the exact manifest bytes stored in the archive.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::utils::{config_file, write_config_file};
+
+const IDENTITY_FILE: &str = "signing_key.json";
+const TRUSTED_KEYS_FILE: &str = "trusted_keys.json";
+
+/// The local key pair used to sign exports
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SigningIdentity {
+    pub name: String,
+    /// Hex-encoded Ed25519 secret key
+    secret_key: String,
+}
+
+/// A public key whose signatures are accepted as trusted
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrustedKey {
+    pub name: String,
+    /// Hex-encoded Ed25519 public key
+    pub public_key: String,
+}
+
+/// Signature stored next to the manifest inside the archive
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    pub signer: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// Result of checking an archive's manifest signature
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureStatus {
+    /// The archive carries no signature
+    Unsigned,
+    /// Valid signature from a key in the trusted keys list
+    Trusted { signer: String },
+    /// Valid signature from a key that is not trusted yet
+    Untrusted { signer: String, public_key: String },
+    /// The signature does not match the manifest or the files do not match the manifest
+    Tampered,
+}
+
+impl SigningIdentity {
+    fn signing_key(&self) -> Result<SigningKey, String> {
+        let bytes: [u8; 32] = hex::decode(&self.secret_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| t!("errors.invalid_signing_key").to_string())?;
+        Ok(SigningKey::from_bytes(&bytes))
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        self.signing_key()
+            .map(|key| hex::encode(key.verifying_key().as_bytes()))
+            .unwrap_or_default()
+    }
+}
+
+/// Shortens a hex public key to a fingerprint for display
+pub fn fingerprint(public_key: &str) -> String {
+    public_key.chars().take(16).collect()
+}
+
+/// Creates a new key pair, stores it and adds its public key to the trusted keys
+pub fn generate_identity(name: &str) -> Result<SigningIdentity, String> {
+    let mut secret = [0u8; 32];
+    getrandom::getrandom(&mut secret)
+        .map_err(|e| t!("errors.key_generation_failed", error = e.to_string()).to_string())?;
+
+    let identity = SigningIdentity {
+        name: name.to_string(),
+        secret_key: hex::encode(secret),
+    };
+    let json = serde_json::to_vec_pretty(&identity)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+    write_config_file(IDENTITY_FILE, &json)?;
+
+    let mut trusted = load_trusted_keys();
+    let public_key = identity.public_key_hex();
+    if !trusted.iter().any(|key| key.public_key == public_key) {
+        trusted.push(TrustedKey {
+            name: name.to_string(),
+            public_key,
+        });
+        save_trusted_keys(&trusted)?;
+    }
+
+    Ok(identity)
+}
+
+/// Loads the local signing identity, if one was generated
+pub fn load_identity() -> Option<SigningIdentity> {
+    let contents = fs::read(config_file(IDENTITY_FILE).ok()?).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+/// Loads the list of trusted public keys
+pub fn load_trusted_keys() -> Vec<TrustedKey> {
+    config_file(TRUSTED_KEYS_FILE)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Stores the list of trusted public keys
+pub fn save_trusted_keys(keys: &[TrustedKey]) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(keys)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())?;
+    write_config_file(TRUSTED_KEYS_FILE, &json)
+}
+
+/// Checks that a string is a valid hex-encoded Ed25519 public key
+pub fn parse_public_key(public_key: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(public_key.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Signs the exact manifest bytes stored in the archive
+pub fn sign_manifest(
+    identity: &SigningIdentity,
+    manifest: &[u8],
+) -> Result<ManifestSignature, String> {
+    let key = identity.signing_key()?;
+    Ok(ManifestSignature {
+        signer: identity.name.clone(),
+        public_key: hex::encode(key.verifying_key().as_bytes()),
+        signature: hex::encode(key.sign(manifest).to_bytes()),
+    })
+}
+
+/// Verifies a manifest signature and looks up the signer in the trusted keys
+pub fn check_signature(
+    manifest: &[u8],
+    signature: Option<&ManifestSignature>,
+    trusted: &[TrustedKey],
+) -> SignatureStatus {
+    let Some(signature) = signature else {
+        return SignatureStatus::Unsigned;
+    };
+
+    let Some(key) = parse_public_key(&signature.public_key) else {
+        return SignatureStatus::Tampered;
+    };
+    let Some(bytes) = hex::decode(&signature.signature)
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+    else {
+        return SignatureStatus::Tampered;
+    };
+
+    if key
+        .verify(manifest, &Signature::from_bytes(&bytes))
+        .is_err()
+    {
+        return SignatureStatus::Tampered;
+    }
+
+    let public_key = hex::encode(key.as_bytes());
+    match trusted
+        .iter()
+        .find(|trusted| trusted.public_key == public_key)
+    {
+        // Show the name the user gave the key, not the one claimed in the archive
+        Some(trusted) => SignatureStatus::Trusted {
+            signer: trusted.name.clone(),
+        },
+        None => SignatureStatus::Untrusted {
+            signer: signature.signer.clone(),
+            public_key,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_identity() -> SigningIdentity {
+        SigningIdentity {
+            name: "Admin".to_string(),
+            secret_key: hex::encode([7u8; 32]),
+        }
+    }
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!(check_signature(b"{}", None, &[]), SignatureStatus::Unsigned);
+    }
+
+    #[test]
+    fn test_trusted_signature() {
+        let identity = test_identity();
+        let signature = sign_manifest(&identity, b"manifest").unwrap();
+        let trusted = vec![TrustedKey {
+            name: "Server Admins".to_string(),
+            public_key: identity.public_key_hex(),
+        }];
+
+        assert_eq!(
+            check_signature(b"manifest", Some(&signature), &trusted),
+            SignatureStatus::Trusted {
+                signer: "Server Admins".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_untrusted_signature() {
+        let identity = test_identity();
+        let signature = sign_manifest(&identity, b"manifest").unwrap();
+
+        assert_eq!(
+            check_signature(b"manifest", Some(&signature), &[]),
+            SignatureStatus::Untrusted {
+                signer: "Admin".to_string(),
+                public_key: identity.public_key_hex(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tampered_manifest() {
+        let identity = test_identity();
+        let signature = sign_manifest(&identity, b"manifest").unwrap();
+
+        assert_eq!(
+            check_signature(b"manifest!", Some(&signature), &[]),
+            SignatureStatus::Tampered
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Snapshot of a world folder taken right before archiving
+//!
+//! Copying a world into a staging folder is much faster than compressing it,
+//! so archiving from a snapshot shortens the window in which a running game
+//! can change files under the export. Files are reflinked on file systems with
+//! copy-on-write support (Btrfs, XFS, APFS, ReFS) and copied everywhere else.
+//! Hard links are not used: the game rewrites region files in place, which
+//! would change the snapshot as well.
+
+use rust_i18n::t;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::consistency::FileState;
+
+/// A frozen copy of a world in a hidden staging folder next to it
+///
+/// The staging folder is removed again when the snapshot is dropped.
+pub struct Snapshot {
+    path: PathBuf,
+}
+
+impl Snapshot {
+    /// Location of the staging folder for a world
+    pub fn staging_path(world_path: &Path) -> PathBuf {
+        let mut name = OsString::from(".");
+        name.push(world_path.file_name().unwrap_or_default());
+        name.push(".snapshot");
+        world_path.with_file_name(name)
+    }
+
+    /// Copies all files that are not excluded into the staging folder
+    ///
+    /// `on_file` is called with the relative path of every file before it is
+    /// copied. Returns the snapshot together with the files that changed while
+    /// they were being copied.
+    pub fn create(
+        world_path: &Path,
+        exclude: impl Fn(&Path) -> bool,
+        mut on_file: impl FnMut(&str),
+    ) -> Result<(Self, Vec<String>), String> {
+        let snapshot = Self {
+            path: Self::staging_path(world_path),
+        };
+
+        // A leftover from an interrupted export
+        if snapshot.path.exists() {
+            fs::remove_dir_all(&snapshot.path)
+                .map_err(|e| t!("errors.snapshot_failed", error = e.to_string()).to_string())?;
+        }
+
+        let mut changed_files = Vec::new();
+        for entry in WalkDir::new(world_path) {
+            let entry = entry
+                .map_err(|e| t!("errors.read_files_failed", error = e.to_string()).to_string())?;
+            let path = entry.path();
+            if exclude(path) {
+                continue;
+            }
+
+            let name = path
+                .strip_prefix(world_path)
+                .map_err(|e| t!("errors.process_path_failed", error = e.to_string()).to_string())?;
+            let target = snapshot.path.join(name);
+
+            if path.is_dir() {
+                fs::create_dir_all(&target)
+                    .map_err(|e| t!("errors.snapshot_failed", error = e.to_string()).to_string())?;
+            } else if path.is_file() {
+                let name = name.to_string_lossy();
+                on_file(&name);
+
+                let state_before = FileState::read(path);
+                reflink_copy::reflink_or_copy(path, &target)
+                    .map_err(|e| t!("errors.snapshot_failed", error = e.to_string()).to_string())?;
+                if state_before.is_none() || FileState::read(path) != state_before {
+                    changed_files.push(name.to_string());
+                }
+            }
+        }
+
+        Ok((snapshot, changed_files))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_copies_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let world = dir.path().join("World");
+        fs::create_dir_all(world.join("chunks")).unwrap();
+        fs::create_dir_all(world.join("logs")).unwrap();
+        fs::write(world.join("config.json"), b"{}").unwrap();
+        fs::write(world.join("chunks/0.0.region"), b"region").unwrap();
+        fs::write(world.join("logs/server.log"), b"log").unwrap();
+
+        let mut seen = Vec::new();
+        let (snapshot, changed) = Snapshot::create(
+            &world,
+            |path| path.ends_with("logs/server.log"),
+            |name| seen.push(name.to_string()),
+        )
+        .unwrap();
+        let snapshot_path = snapshot.path().to_path_buf();
+
+        assert_eq!(snapshot_path, dir.path().join(".World.snapshot"));
+        assert!(changed.is_empty());
+        assert_eq!(seen.len(), 2);
+        assert_eq!(
+            fs::read(snapshot_path.join("chunks/0.0.region")).unwrap(),
+            b"region"
+        );
+        assert!(!snapshot_path.join("logs/server.log").exists());
+
+        // Writes to the world after the snapshot must not show up in it
+        fs::write(world.join("chunks/0.0.region"), b"changed").unwrap();
+        assert_eq!(
+            fs::read(snapshot_path.join("chunks/0.0.region")).unwrap(),
+            b"region"
+        );
+
+        drop(snapshot);
+        assert!(!snapshot_path.exists());
+    }
+}
@@ -0,0 +1,251 @@
+//! Filename templates for exports
+//!
+//! A template is plain text with placeholders in braces:
+//!
+//! - `{world}`: world name
+//! - `{timestamp}`, `{date}`, `{time}`: export time as `2026-01-13_19-35-06`,
+//!   `2026-01-13` and `19-35-06`
+//! - `{date:FORMAT}`: export time in a custom chrono format, e.g. `{date:%Y%m%d}`
+//! - `{hostname}`: name of this computer
+//! - `{size}`: world size, e.g. `1.5GB`, or `unknown-size` while it is still
+//!   being calculated
+//! - `{counter}` or `{counter:WIDTH}`: lowest number that gives an unused file name
+//! - `{tags}`: the configured tags joined with `-`
+//!
+//! `{{` and `}}` produce literal braces. Characters that are not allowed in
+//! file names are replaced with `_`.
+
+use chrono::{DateTime, Local};
+use rust_i18n::t;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::utils::format_size;
+
+/// Values available to a template
+pub struct TemplateContext<'a> {
+    pub world: &'a str,
+    pub time: DateTime<Local>,
+    pub hostname: &'a str,
+    /// World size, `None` if it is not known yet
+    pub size: Option<u64>,
+    pub tags: &'a [String],
+}
+
+enum Part<'a> {
+    Text(String),
+    Placeholder(&'a str, Option<&'a str>),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        text.push_str(&rest[..pos]);
+        let brace = &rest[pos..];
+
+        if brace.starts_with("{{") || brace.starts_with("}}") {
+            text.push_str(&brace[..1]);
+            rest = &brace[2..];
+        } else if brace.starts_with('}') {
+            return Err(t!("errors.template_unmatched_brace").to_string());
+        } else {
+            let end = brace
+                .find('}')
+                .ok_or_else(|| t!("errors.template_unmatched_brace").to_string())?;
+            let placeholder = &brace[1..end];
+            let (name, argument) = match placeholder.split_once(':') {
+                Some((name, argument)) => (name, Some(argument)),
+                None => (placeholder, None),
+            };
+            if !matches!(
+                name,
+                "world" | "timestamp" | "date" | "time" | "hostname" | "size" | "counter" | "tags"
+            ) {
+                return Err(t!("errors.template_unknown_placeholder", name = name).to_string());
+            }
+
+            parts.push(Part::Text(std::mem::take(&mut text)));
+            parts.push(Part::Placeholder(name, argument));
+            rest = &brace[end + 1..];
+        }
+    }
+    text.push_str(rest);
+    parts.push(Part::Text(text));
+
+    Ok(parts)
+}
+
+/// Renders a template with a given counter value
+pub fn render(template: &str, context: &TemplateContext, counter: u32) -> Result<String, String> {
+    let mut name = String::new();
+
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => name.push_str(&text),
+            Part::Placeholder(placeholder, argument) => match (placeholder, argument) {
+                ("world", _) => name.push_str(context.world),
+                ("timestamp", _) => {
+                    let _ = write!(name, "{}", context.time.format("%Y-%m-%d_%H-%M-%S"));
+                }
+                ("date", Some(format)) => {
+                    // Invalid chrono formats fail while being written instead of panicking
+                    write!(name, "{}", context.time.format(format)).map_err(|_| {
+                        t!("errors.template_invalid_date", format = format).to_string()
+                    })?;
+                }
+                ("date", None) => {
+                    let _ = write!(name, "{}", context.time.format("%Y-%m-%d"));
+                }
+                ("time", _) => {
+                    let _ = write!(name, "{}", context.time.format("%H-%M-%S"));
+                }
+                ("hostname", _) => name.push_str(context.hostname),
+                ("size", _) => match context.size {
+                    Some(size) => name.push_str(&format_size(size).replace(' ', "")),
+                    None => name.push_str("unknown-size"),
+                },
+                ("counter", width) => {
+                    let width = width.and_then(|width| width.parse().ok()).unwrap_or(1);
+                    let _ = write!(name, "{:0width$}", counter, width = width);
+                }
+                ("tags", _) => name.push_str(&context.tags.join("-")),
+                _ => {}
+            },
+        }
+    }
+
+    Ok(sanitize(&name))
+}
+
+/// Checks whether a template contains the given placeholder
+pub fn uses_placeholder(template: &str, placeholder: &str) -> bool {
+    parse(template).is_ok_and(|parts| {
+        parts
+            .iter()
+            .any(|part| matches!(part, Part::Placeholder(name, _) if *name == placeholder))
+    })
+}
+
+/// Renders a template to a file name that does not exist in the directory yet
+///
+/// Only templates with a `{counter}` are searched for a free name; others are
+/// rendered once, the save dialog asks before overwriting.
+pub fn render_unique(
+    template: &str,
+    context: &TemplateContext,
+    directory: Option<&Path>,
+    extension: &str,
+) -> Result<String, String> {
+    parse(template)?;
+    let uses_counter = uses_placeholder(template, "counter");
+
+    let mut counter = 1;
+    loop {
+        let name = format!("{}.{}", render(template, context, counter)?, extension);
+        let taken = directory.is_some_and(|directory| directory.join(&name).exists());
+        if !uses_counter || !taken {
+            return Ok(name);
+        }
+        counter += 1;
+    }
+}
+
+/// Replaces characters that are not allowed in file names
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn context(tags: &[String]) -> TemplateContext<'_> {
+        TemplateContext {
+            world: "My World",
+            time: Local.with_ymd_and_hms(2026, 1, 13, 19, 35, 6).unwrap(),
+            hostname: "gaming-pc",
+            size: Some(1536 * 1024 * 1024),
+            tags,
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let tags = vec!["weekly".to_string(), "pre-update".to_string()];
+        let context = context(&tags);
+
+        assert_eq!(
+            render("{world}_{timestamp}", &context, 1).unwrap(),
+            "My World_2026-01-13_19-35-06"
+        );
+        assert_eq!(
+            render("{date:%Y%m%d}-{time}-{hostname}", &context, 1).unwrap(),
+            "20260113-19-35-06-gaming-pc"
+        );
+        assert_eq!(
+            render("{world} {size} {tags} #{counter:3}", &context, 7).unwrap(),
+            "My World 1.50GB weekly-pre-update #007"
+        );
+        assert_eq!(render("{{{world}}}", &context, 1).unwrap(), "{My World}");
+    }
+
+    #[test]
+    fn test_unknown_size() {
+        let context = TemplateContext {
+            size: None,
+            ..context(&[])
+        };
+        assert_eq!(
+            render("{world}_{size}", &context, 1).unwrap(),
+            "My World_unknown-size"
+        );
+        assert!(uses_placeholder("{world}_{size}", "size"));
+        assert!(!uses_placeholder("{world}_{{size}}", "size"));
+    }
+
+    #[test]
+    fn test_sanitizes_file_names() {
+        let context = context(&[]);
+        assert_eq!(
+            render("{date:%d/%m/%Y %H:%M}", &context, 1).unwrap(),
+            "13_01_2026 19_35"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        let context = context(&[]);
+        assert!(render("{world", &context, 1).is_err());
+        assert!(render("world}", &context, 1).is_err());
+        assert!(render("{unknown}", &context, 1).is_err());
+        assert!(render("{date:%Q}", &context, 1).is_err());
+        assert!(render("{world}_{date:%Y}", &context, 1).is_ok());
+    }
+
+    #[test]
+    fn test_render_unique_counter() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = context(&[]);
+        std::fs::write(dir.path().join("My World-1.zip"), b"").unwrap();
+        std::fs::write(dir.path().join("My World-2.zip"), b"").unwrap();
+
+        assert_eq!(
+            render_unique("{world}-{counter}", &context, Some(dir.path()), "zip").unwrap(),
+            "My World-3.zip"
+        );
+        assert_eq!(
+            render_unique("{world}", &context, Some(dir.path()), "zip").unwrap(),
+            "My World.zip"
+        );
+    }
+}
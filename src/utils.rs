@@ -1,7 +1,9 @@
+use rust_i18n::t;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::game_backup::parse_name_time;
 use crate::models::{BackupInfo, LogInfo};
 
 /// Detects the system locale and returns the language code
@@ -14,6 +16,64 @@ pub fn detect_system_locale() -> String {
         .unwrap_or_else(|| "en".to_string())
 }
 
+/// Returns the directory for the application's own configuration files
+pub fn app_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("hytale-world-exporter"))
+}
+
+/// Returns the path of a file in the application's config directory
+pub fn config_file(name: &str) -> Result<PathBuf, String> {
+    app_config_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| t!("errors.config_dir_not_found").to_string())
+}
+
+/// Writes a file to the application's config directory
+pub fn write_config_file(name: &str, contents: &[u8]) -> Result<(), String> {
+    let path = config_file(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| t!("errors.create_dir_failed", error = e.to_string()).to_string())?;
+    }
+    write_private_file(&path, contents)
+        .map_err(|e| t!("errors.write_file_failed", error = e.to_string()).to_string())
+}
+
+/// Replaces a file with new contents that only the user can read
+///
+/// Config files can hold secret keys, so the contents go into a temporary file
+/// that is private from the start and is then renamed over the old one. A
+/// crash while writing therefore never leaves a half-written file behind.
+pub fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    // The mode only applies to new files, so never reuse a leftover
+    let _ = fs::remove_file(&temp_path);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = options.open(&temp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    match result.and_then(|_| fs::rename(&temp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
 /// Formats bytes into human-readable size string
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -32,7 +92,7 @@ pub fn format_size(bytes: u64) -> String {
 }
 
 /// Calculates the total size of a directory recursively
-pub fn get_directory_size(path: &PathBuf) -> u64 {
+pub fn get_directory_size(path: &Path) -> u64 {
     WalkDir::new(path)
         .into_iter()
         .filter_map(|e| e.ok())
@@ -43,7 +103,7 @@ pub fn get_directory_size(path: &PathBuf) -> u64 {
 }
 
 /// Gets the last played timestamp from log files
-pub fn get_last_played(world_path: &PathBuf) -> Option<String> {
+pub fn get_last_played(world_path: &Path) -> Option<String> {
     let logs_path = world_path.join("logs");
     if !logs_path.exists() {
         return None;
@@ -54,12 +114,12 @@ pub fn get_last_played(world_path: &PathBuf) -> Option<String> {
         .filter_map(|entry| entry.ok())
         .filter(|entry| {
             entry.path().is_file() &&
-            entry.path().extension().map_or(false, |ext| ext == "log")
+            entry.path().extension().is_some_and(|ext| ext == "log")
         })
         .collect();
 
     // Sort by filename descending (newest first based on timestamp in filename)
-    logs.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    logs.sort_by_key(|entry| std::cmp::Reverse(entry.file_name()));
 
     logs.first().and_then(|entry| {
         let name = entry.file_name().to_str()?.to_string();
@@ -76,7 +136,7 @@ pub fn get_last_played(world_path: &PathBuf) -> Option<String> {
 }
 
 /// Gets backup files for a world, filtering out system files
-pub fn get_world_backups(world_path: &PathBuf) -> Vec<BackupInfo> {
+fn world_backup_files(world_path: &Path) -> Vec<fs::DirEntry> {
     let backup_path = world_path.join("backup");
     if !backup_path.exists() {
         return Vec::new();
@@ -96,19 +156,46 @@ pub fn get_world_backups(world_path: &PathBuf) -> Vec<BackupInfo> {
                     !name_str.starts_with("desktop.ini") &&
                     !name_str.starts_with("._")
                 })
-                .filter_map(|entry| {
-                    let name = entry.file_name().to_str()?.to_string();
-                    let path = entry.path();
-                    let size = entry.metadata().ok()?.len();
-                    Some(BackupInfo { name, path, size })
-                })
                 .collect()
         })
         .unwrap_or_default()
 }
 
+/// Counts the backups of a world without opening them
+pub fn count_world_backups(world_path: &Path) -> usize {
+    world_backup_files(world_path).len()
+}
+
+/// Gets the backups of a world with their times, without opening them
+pub fn get_world_backups(world_path: &Path) -> Vec<BackupInfo> {
+    world_backup_files(world_path)
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = entry.path();
+            let metadata = entry.metadata().ok()?;
+            Some(BackupInfo {
+                name_time: parse_name_time(&name),
+                name,
+                path,
+                size: metadata.len(),
+                created: metadata.created().ok(),
+                modified: metadata.modified().ok(),
+            })
+        })
+        .collect()
+}
+
 /// Gets the latest log file for a world
-pub fn get_latest_log(world_path: &PathBuf) -> Option<LogInfo> {
+pub fn get_latest_log(world_path: &Path) -> Option<LogInfo> {
+    let path = get_latest_log_path(world_path)?;
+    let name = path.file_name()?.to_str()?.to_string();
+    let content = fs::read_to_string(&path).unwrap_or_else(|_| String::from("Could not read log file"));
+    Some(LogInfo { name, path, content })
+}
+
+/// Path of the newest log file of a world
+pub fn get_latest_log_path(world_path: &Path) -> Option<PathBuf> {
     let logs_path = world_path.join("logs");
     if !logs_path.exists() {
         return None;
@@ -119,23 +206,18 @@ pub fn get_latest_log(world_path: &PathBuf) -> Option<LogInfo> {
         .filter_map(|entry| entry.ok())
         .filter(|entry| {
             entry.path().is_file() &&
-            entry.path().extension().map_or(false, |ext| ext == "log")
+            entry.path().extension().is_some_and(|ext| ext == "log")
         })
         .collect();
 
     // Sort by filename descending (newest first based on timestamp in filename)
-    logs.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    logs.sort_by_key(|entry| std::cmp::Reverse(entry.file_name()));
 
-    logs.first().and_then(|entry| {
-        let name = entry.file_name().to_str()?.to_string();
-        let path = entry.path();
-        let content = fs::read_to_string(&path).unwrap_or_else(|_| String::from("Could not read log file"));
-        Some(LogInfo { name, path, content })
-    })
+    logs.first().map(|entry| entry.path())
 }
 
 /// Opens a file in the system file manager
-pub fn open_file_in_finder(path: &PathBuf) {
+pub fn open_file_in_finder(path: &Path) {
     #[cfg(target_os = "macos")]
     {
         let _ = std::process::Command::new("open")
@@ -162,3 +244,25 @@ pub fn open_file_in_finder(path: &PathBuf) {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_private_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identity.json");
+        fs::write(&path, "old").unwrap();
+
+        write_private_file(&path, b"secret").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,327 @@
+//! Multi-volume archive support
+//!
+//! Archives can be split into numbered volumes (`world.zip.001`, `world.zip.002`, ...)
+//! of a fixed maximum size. The volumes are plain byte-wise parts of one ZIP file,
+//! so joining them in order gives back the original archive.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Extension of the first volume of a split archive
+const FIRST_VOLUME_EXTENSION: &str = "001";
+
+/// Returns the path of the volume with the given 1-based number
+pub fn volume_path(base: &Path, number: usize) -> PathBuf {
+    let mut name = base.as_os_str().to_owned();
+    name.push(format!(".{:03}", number));
+    PathBuf::from(name)
+}
+
+/// Checks whether the path points to the first volume of a split archive
+pub fn is_first_volume(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == FIRST_VOLUME_EXTENSION)
+}
+
+/// Checks whether the path points to any volume of a split archive
+pub fn is_volume(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.len() == 3 && ext.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Collects all volumes belonging to the split archive starting at `first`
+pub fn collect_volumes(first: &Path) -> Vec<PathBuf> {
+    let base = first.with_extension("");
+    (1..)
+        .map(|number| volume_path(&base, number))
+        .take_while(|path| path.is_file())
+        .collect()
+}
+
+/// Size of an archive, adding up all volumes of a split one
+pub fn archive_size(path: &Path) -> u64 {
+    let files = if is_first_volume(path) {
+        collect_volumes(path)
+    } else {
+        vec![path.to_path_buf()]
+    };
+    files
+        .iter()
+        .filter_map(|file| fs::metadata(file).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Writer that spreads its output over numbered volumes of a maximum size
+///
+/// Seeking is supported across volume boundaries, which the ZIP writer needs
+/// to patch local headers after an entry has been written.
+pub struct VolumeWriter {
+    base: PathBuf,
+    max_size: u64,
+    volumes: Vec<File>,
+    position: u64,
+    length: u64,
+}
+
+impl VolumeWriter {
+    /// Creates a writer for `base`; without a `max_size` everything goes into one file
+    pub fn create(base: &Path, max_size: Option<u64>) -> io::Result<Self> {
+        let mut writer = Self {
+            base: base.to_path_buf(),
+            max_size: max_size.unwrap_or(u64::MAX).max(1),
+            volumes: Vec::new(),
+            position: 0,
+            length: 0,
+        };
+        writer.open_volume()?;
+        Ok(writer)
+    }
+
+    fn is_split(&self) -> bool {
+        self.max_size != u64::MAX
+    }
+
+    fn open_volume(&mut self) -> io::Result<()> {
+        let path = if self.is_split() {
+            volume_path(&self.base, self.volumes.len() + 1)
+        } else {
+            self.base.clone()
+        };
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        self.volumes.push(file);
+        Ok(())
+    }
+
+    /// Flushes all volumes and returns their paths
+    ///
+    /// If a split archive ended up fitting into a single volume, that volume
+    /// is renamed to the base path so no needless `.001` file is left behind.
+    pub fn finish(mut self) -> io::Result<Vec<PathBuf>> {
+        for volume in &mut self.volumes {
+            volume.flush()?;
+        }
+
+        if !self.is_split() {
+            // Volumes of an earlier split export would be taken for this archive
+            remove_volumes_from(&self.base, 1)?;
+            return Ok(vec![self.base]);
+        }
+
+        let count = self.volumes.len();
+        drop(self.volumes);
+
+        // Remove leftovers of an earlier, larger export to the same path
+        remove_volumes_from(&self.base, count + 1)?;
+
+        if count == 1 {
+            fs::rename(volume_path(&self.base, 1), &self.base)?;
+            return Ok(vec![self.base]);
+        }
+
+        // An earlier unsplit export to the same path is stale as well
+        if self.base.is_file() {
+            fs::remove_file(&self.base)?;
+        }
+
+        Ok((1..=count)
+            .map(|number| volume_path(&self.base, number))
+            .collect())
+    }
+}
+
+/// Deletes the volumes of `base` from the given number on
+fn remove_volumes_from(base: &Path, first: usize) -> io::Result<()> {
+    for path in (first..)
+        .map(|number| volume_path(base, number))
+        .take_while(|path| path.is_file())
+    {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+impl Write for VolumeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let index = (self.position / self.max_size) as usize;
+        let offset = self.position % self.max_size;
+        while self.volumes.len() <= index {
+            self.open_volume()?;
+        }
+
+        let room = (self.max_size - offset).min(buf.len() as u64) as usize;
+        let volume = &mut self.volumes[index];
+        volume.seek(SeekFrom::Start(offset))?;
+        let written = volume.write(&buf[..room])?;
+
+        self.position += written as u64;
+        self.length = self.length.max(self.position);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.volumes
+            .iter_mut()
+            .try_for_each(|volume| volume.flush())
+    }
+}
+
+impl Seek for VolumeWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = resolve_seek(pos, self.position, self.length)?;
+        Ok(self.position)
+    }
+}
+
+/// Reader that presents one or more volumes as a single continuous file
+pub struct VolumeReader {
+    volumes: Vec<(File, u64)>,
+    position: u64,
+    length: u64,
+}
+
+impl VolumeReader {
+    /// Opens an archive; a first volume (`.001`) pulls in all following volumes
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let paths = if is_first_volume(path) {
+            collect_volumes(path)
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut volumes = Vec::with_capacity(paths.len());
+        for path in paths {
+            let file = File::open(&path)?;
+            let size = file.metadata()?.len();
+            volumes.push((file, size));
+        }
+
+        let length = volumes.iter().map(|(_, size)| size).sum();
+        Ok(Self {
+            volumes,
+            position: 0,
+            length,
+        })
+    }
+}
+
+impl Read for VolumeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (file, size) in &mut self.volumes {
+            if self.position < start + *size {
+                let offset = self.position - start;
+                let room = (*size - offset).min(buf.len() as u64) as usize;
+                file.seek(SeekFrom::Start(offset))?;
+                let read = file.read(&mut buf[..room])?;
+                self.position += read as u64;
+                return Ok(read);
+            }
+            start += *size;
+        }
+        Ok(0)
+    }
+}
+
+impl Seek for VolumeReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = resolve_seek(pos, self.position, self.length)?;
+        Ok(self.position)
+    }
+}
+
+fn resolve_seek(pos: SeekFrom, current: u64, length: u64) -> io::Result<u64> {
+    let target = match pos {
+        SeekFrom::Start(offset) => Some(offset),
+        SeekFrom::Current(delta) => current.checked_add_signed(delta),
+        SeekFrom::End(delta) => length.checked_add_signed(delta),
+    };
+    target.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek position"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_naming() {
+        let base = Path::new("/tmp/world.zip");
+        assert_eq!(volume_path(base, 1), PathBuf::from("/tmp/world.zip.001"));
+        assert_eq!(volume_path(base, 12), PathBuf::from("/tmp/world.zip.012"));
+        assert!(is_first_volume(Path::new("world.zip.001")));
+        assert!(!is_first_volume(Path::new("world.zip.002")));
+        assert!(is_volume(Path::new("world.zip.002")));
+        assert!(!is_volume(Path::new("world.zip")));
+    }
+
+    #[test]
+    fn test_split_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("world.zip");
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+        // Left over from an earlier unsplit export to the same path
+        fs::write(&base, "stale").unwrap();
+
+        let mut writer = VolumeWriter::create(&base, Some(4096)).unwrap();
+        writer.write_all(&data).unwrap();
+        // Patch bytes across a volume boundary like the ZIP writer does for headers
+        writer.seek(SeekFrom::Start(4090)).unwrap();
+        writer.write_all(&[0xAA; 12]).unwrap();
+        let volumes = writer.finish().unwrap();
+
+        assert_eq!(volumes.len(), 3);
+        assert!(!base.exists());
+        assert_eq!(fs::metadata(&volumes[0]).unwrap().len(), 4096);
+        assert_eq!(collect_volumes(&volumes[0]), volumes);
+
+        let mut expected = data.clone();
+        expected[4090..4102].fill(0xAA);
+        let mut reader = VolumeReader::open(&volumes[0]).unwrap();
+        let mut joined = Vec::new();
+        reader.read_to_end(&mut joined).unwrap();
+        assert_eq!(joined, expected);
+
+        reader.seek(SeekFrom::End(-2)).unwrap();
+        let mut tail = [0u8; 2];
+        reader.read_exact(&mut tail).unwrap();
+        assert_eq!(tail, expected[expected.len() - 2..]);
+    }
+
+    #[test]
+    fn test_single_volume_keeps_base_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("world.zip");
+
+        let mut writer = VolumeWriter::create(&base, Some(1024)).unwrap();
+        writer.write_all(b"small archive").unwrap();
+        let volumes = writer.finish().unwrap();
+
+        assert_eq!(volumes, vec![base.clone()]);
+        assert!(!volume_path(&base, 1).exists());
+    }
+
+    #[test]
+    fn test_unsplit_removes_stale_volumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("world.zip");
+        fs::write(volume_path(&base, 1), "stale").unwrap();
+        fs::write(volume_path(&base, 2), "stale").unwrap();
+
+        let mut writer = VolumeWriter::create(&base, None).unwrap();
+        writer.write_all(b"archive").unwrap();
+        assert_eq!(writer.finish().unwrap(), vec![base.clone()]);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Watching save roots for changes
+//!
+//! Worlds, their backups and logs change while the game runs. A watcher on
+//! every save root and server install collects what changed, and the app picks
+//! the changes up once the files have been quiet for a moment.
+
+use eframe::egui;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Time without new events before changes are handed out
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+
+/// Longest time changes are held back while events keep coming in
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Changes collected since they were last taken
+#[derive(Debug, Default, PartialEq)]
+pub struct FileChanges {
+    /// Folders were added to or removed from a root, so the world list is outdated
+    pub world_list: bool,
+    /// Worlds whose files changed
+    pub worlds: HashSet<PathBuf>,
+}
+
+#[derive(Default)]
+struct Pending {
+    changes: FileChanges,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
+}
+
+/// Watches save roots and server installs recursively
+#[derive(Default)]
+pub struct WorldWatcher {
+    roots: Vec<PathBuf>,
+    pending: Arc<Mutex<Pending>>,
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl WorldWatcher {
+    /// Starts watching the given roots
+    ///
+    /// Watching is best effort: roots that cannot be watched are only updated
+    /// by a manual refresh.
+    pub fn new(roots: Vec<PathBuf>, ctx: &egui::Context) -> Self {
+        let pending = Arc::new(Mutex::new(Pending::default()));
+
+        let handler = {
+            let roots = roots.clone();
+            let pending = Arc::clone(&pending);
+            let ctx = ctx.clone();
+            move |result: notify::Result<Event>| {
+                let Ok(event) = result else {
+                    return;
+                };
+                // Reading files, e.g. during an export, changes nothing
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+
+                let mut pending = pending.lock().unwrap();
+                for path in &event.paths {
+                    classify_change(&roots, path, &mut pending.changes);
+                }
+                let now = Instant::now();
+                pending.first_event.get_or_insert(now);
+                pending.last_event = Some(now);
+                ctx.request_repaint_after(QUIET_PERIOD);
+            }
+        };
+
+        let watcher = notify::recommended_watcher(handler)
+            .ok()
+            .map(|mut watcher| {
+                for root in &roots {
+                    let _ = watcher.watch(root, RecursiveMode::Recursive);
+                }
+                watcher
+            });
+
+        Self {
+            roots,
+            pending,
+            _watcher: watcher,
+        }
+    }
+
+    /// The watched roots
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Takes the collected changes once events have settled
+    pub fn take_changes(&self) -> Option<FileChanges> {
+        let mut pending = self.pending.lock().unwrap();
+        let settled = pending
+            .last_event
+            .is_some_and(|last| last.elapsed() >= QUIET_PERIOD)
+            || pending
+                .first_event
+                .is_some_and(|first| first.elapsed() >= MAX_DELAY);
+        if !settled {
+            return None;
+        }
+
+        let pending = std::mem::take(&mut *pending);
+        Some(pending.changes)
+    }
+}
+
+/// Records which world a changed path belongs to
+///
+/// Worlds are the direct subfolders of a root, so a change directly inside a
+/// root affects the world list and a deeper change affects a single world.
+/// Hidden folders are staging areas of imports and snapshots and are ignored.
+fn classify_change(roots: &[PathBuf], path: &Path, changes: &mut FileChanges) {
+    // With nested roots the innermost one owns the path
+    let Some((root, relative)) = roots
+        .iter()
+        .filter_map(|root| Some((root, path.strip_prefix(root).ok()?)))
+        .max_by_key(|(root, _)| root.components().count())
+    else {
+        return;
+    };
+
+    let mut components = relative.components();
+    let Some(Component::Normal(world)) = components.next() else {
+        return;
+    };
+    if world.to_string_lossy().starts_with('.') {
+        return;
+    }
+
+    if components.next().is_none() {
+        changes.world_list = true;
+    } else {
+        changes.worlds.insert(root.join(world));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_change() {
+        let roots = vec![PathBuf::from("/saves"), PathBuf::from("/server")];
+        let mut changes = FileChanges::default();
+
+        classify_change(&roots, Path::new("/saves/world/backup/a.zip"), &mut changes);
+        classify_change(&roots, Path::new("/saves/world/logs/b.log"), &mut changes);
+        classify_change(&roots, Path::new("/server/universe/worlds/x"), &mut changes);
+        assert!(!changes.world_list);
+        assert_eq!(
+            changes.worlds,
+            HashSet::from([
+                PathBuf::from("/saves/world"),
+                PathBuf::from("/server/universe")
+            ])
+        );
+
+        // Staging folders and paths outside of the roots are ignored
+        classify_change(&roots, Path::new("/saves/.world.snapshot/a"), &mut changes);
+        classify_change(&roots, Path::new("/saves/.world.snapshot"), &mut changes);
+        classify_change(&roots, Path::new("/elsewhere/world/a"), &mut changes);
+        assert!(!changes.world_list);
+        assert_eq!(changes.worlds.len(), 2);
+
+        classify_change(&roots, Path::new("/saves/new world"), &mut changes);
+        assert!(changes.world_list);
+    }
+
+    #[test]
+    fn test_nested_roots() {
+        let roots = vec![PathBuf::from("/data"), PathBuf::from("/data/saves")];
+        let mut changes = FileChanges::default();
+
+        classify_change(&roots, Path::new("/data/saves/world/a"), &mut changes);
+        assert_eq!(
+            changes.worlds,
+            HashSet::from([PathBuf::from("/data/saves/world")])
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Listing the worlds of the save roots and dedicated servers
+//!
+//! Shared by the app, the daemon and the command line, so none of them needs
+//! the window to know which worlds there are.
+
+use std::fs;
+
+use crate::models::WorldInfo;
+use crate::server::find_universes;
+use crate::settings::{SaveRoot, ServerProfile};
+
+/// Lists the worlds in the save roots and the universes of the servers
+pub fn load_worlds(roots: &[SaveRoot], servers: &[ServerProfile]) -> Vec<WorldInfo> {
+    let server_worlds = servers.iter().flat_map(|server| {
+        find_universes(&server.path)
+            .into_iter()
+            .filter_map(|path| {
+                Some(WorldInfo {
+                    name: path.file_name()?.to_str()?.to_string(),
+                    details: None,
+                    source: server.name.clone(),
+                    server_dir: Some(server.path.clone()),
+                    path,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    roots
+        .iter()
+        .filter(|root| root.path.exists())
+        .flat_map(|root| {
+            fs::read_dir(&root.path)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.path().is_dir())
+                        // Hidden folders are import and snapshot staging areas
+                        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                        .filter_map(|entry| {
+                            let name = entry.file_name().to_str()?.to_string();
+                            Some(WorldInfo {
+                                name,
+                                path: entry.path(),
+                                details: None,
+                                source: root.name.clone(),
+                                server_dir: None,
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+        .chain(server_worlds)
+        .collect()
+}